@@ -125,6 +125,9 @@ fn action_inner(input: TokenStream) -> TokenStream {
 		}
 	});
 
+	// Sleep for the first action which knows when it might be ready, if none of
+	// them do then block for a tick so a surrounding loop doesn't starve other
+	// tasks. Yielding would only let tasks of the same priority run
 	let i = syn::Index::from(parsed.action_arms.len() - 1);
 	let sleep_match = (0..parsed.action_arms.len() - 1)
 		.rev()
@@ -132,7 +135,9 @@ fn action_inner(input: TokenStream) -> TokenStream {
 		.fold(
 			quote! {
 				match ::pros::rtos::action::Action::next(&mut #actions_ident.#i) {
-					::pros::rtos::action::NextSleep::Never => return #enum_ident::None,
+					::pros::rtos::action::NextSleep::Never => ::pros::rtos::action::NextSleep::sleep(
+						::pros::rtos::action::NextSleep::Timestamp(::core::time::Duration::from_millis(1))
+					),
 					n => ::pros::rtos::action::NextSleep::sleep(n),
 				}
 			},
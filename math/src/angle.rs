@@ -0,0 +1,184 @@
+//! An angle type which keeps track of its units, along with functions for
+//! wrapping plain `f64` angles.
+
+use core::f64::consts::{PI, TAU};
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// An angle, stored internally in radians.
+///
+/// Angles are not automatically wrapped, arithmetic on them will happily count
+/// past a full rotation. Use [`Angle::normalised()`] or [`Angle::wrapped()`]
+/// to bring an angle back into a single rotation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Angle(f64);
+
+impl Angle {
+	/// An angle of zero.
+	pub const ZERO: Angle = Angle(0.0);
+
+	/// Create a new angle from a value in radians.
+	#[inline]
+	pub const fn from_radians(radians: f64) -> Self {
+		Angle(radians)
+	}
+
+	/// Create a new angle from a value in degrees.
+	#[inline]
+	pub fn from_degrees(degrees: f64) -> Self {
+		Angle(degrees * (PI / 180.0))
+	}
+
+	/// Get the value of this angle in radians.
+	#[inline]
+	pub const fn as_radians(self) -> f64 {
+		self.0
+	}
+
+	/// Get the value of this angle in degrees.
+	#[inline]
+	pub fn as_degrees(self) -> f64 {
+		self.0 * (180.0 / PI)
+	}
+
+	/// Wrap this angle into the range of `[0, 2pi)` radians, or `[0, 360)`
+	/// degrees.
+	#[inline]
+	pub fn normalised(self) -> Self {
+		Angle(angle_norm_rad(self.0))
+	}
+
+	/// Wrap this angle into the range of `[-pi, pi)` radians, or `[-180, 180)`
+	/// degrees.
+	#[inline]
+	pub fn wrapped(self) -> Self {
+		Angle(angle_wrap_rad(self.0))
+	}
+
+	/// Get the shortest signed angle which would need to be added to `self`
+	/// to reach `target`. The result is within `[-pi, pi)` radians.
+	#[inline]
+	pub fn error_to(self, target: Angle) -> Self {
+		(target - self).wrapped()
+	}
+}
+
+/// Wrap an angle in degrees into the range of `[-180, 180)`. This is the
+/// form wanted for the error of a heading controller, as it is the shortest
+/// way around to the target.
+///
+/// # Examples
+/// ```
+/// use pros_math::angle::angle_wrap_deg;
+///
+/// // Turning from 359 to 1 degrees is 2 degrees, not -358
+/// assert_eq!(angle_wrap_deg(1.0 - 359.0), 2.0);
+/// assert_eq!(angle_wrap_deg(180.0), -180.0);
+/// ```
+#[inline]
+pub fn angle_wrap_deg(a: f64) -> f64 {
+	rem_euclid(a + 180.0, 360.0) - 180.0
+}
+
+/// Wrap an angle in degrees into the range of `[0, 360)`.
+///
+/// # Examples
+/// ```
+/// use pros_math::angle::angle_norm_deg;
+///
+/// assert_eq!(angle_norm_deg(-90.0), 270.0);
+/// assert_eq!(angle_norm_deg(720.0), 0.0);
+/// ```
+#[inline]
+pub fn angle_norm_deg(a: f64) -> f64 {
+	rem_euclid(a, 360.0)
+}
+
+/// Wrap an angle in radians into the range of `[-pi, pi)`. This is the same
+/// as [`angle_wrap_deg()`] but for radians.
+#[inline]
+pub fn angle_wrap_rad(a: f64) -> f64 {
+	rem_euclid(a + PI, TAU) - PI
+}
+
+/// Wrap an angle in radians into the range of `[0, 2pi)`. This is the same as
+/// [`angle_norm_deg()`] but for radians.
+#[inline]
+pub fn angle_norm_rad(a: f64) -> f64 {
+	rem_euclid(a, TAU)
+}
+
+#[inline]
+fn rem_euclid(a: f64, b: f64) -> f64 {
+	let r = a % b;
+	if r < 0.0 {
+		// A tiny negative remainder can round up to `b` itself, which is
+		// outside of the range
+		let r = r + b;
+		if r < b {
+			r
+		} else {
+			0.0
+		}
+	} else {
+		r
+	}
+}
+
+impl Add for Angle {
+	type Output = Angle;
+
+	#[inline]
+	fn add(self, rhs: Angle) -> Angle {
+		Angle(self.0 + rhs.0)
+	}
+}
+
+impl AddAssign for Angle {
+	#[inline]
+	fn add_assign(&mut self, rhs: Angle) {
+		self.0 += rhs.0;
+	}
+}
+
+impl Sub for Angle {
+	type Output = Angle;
+
+	#[inline]
+	fn sub(self, rhs: Angle) -> Angle {
+		Angle(self.0 - rhs.0)
+	}
+}
+
+impl SubAssign for Angle {
+	#[inline]
+	fn sub_assign(&mut self, rhs: Angle) {
+		self.0 -= rhs.0;
+	}
+}
+
+impl Neg for Angle {
+	type Output = Angle;
+
+	#[inline]
+	fn neg(self) -> Angle {
+		Angle(-self.0)
+	}
+}
+
+impl Mul<f64> for Angle {
+	type Output = Angle;
+
+	#[inline]
+	fn mul(self, rhs: f64) -> Angle {
+		Angle(self.0 * rhs)
+	}
+}
+
+impl Div<f64> for Angle {
+	type Output = Angle;
+
+	#[inline]
+	fn div(self, rhs: f64) -> Angle {
+		Angle(self.0 / rhs)
+	}
+}
@@ -0,0 +1,160 @@
+#![no_std]
+
+//! # PROS maths
+//! Maths types and functions for use with `pros-rs`. These are written to work
+//! in a `no_std` environment, where the floating point functions that would
+//! normally be provided by `std` are instead bound from the C library.
+
+pub mod angle;
+pub mod mat;
+pub mod pid;
+pub mod quat;
+pub mod vec;
+
+pub use angle::Angle;
+pub use mat::{DMat3, DMat4, Mat3, Mat4};
+pub use pid::Pid;
+pub use quat::Quaternion;
+pub use vec::{DVec2, DVec3, DVec4, Vec2, Vec3, Vec4};
+
+mod ffi {
+	extern "C" {
+		pub fn sqrt(x: f64) -> f64;
+		pub fn sqrtf(x: f32) -> f32;
+		pub fn fabs(x: f64) -> f64;
+		pub fn fabsf(x: f32) -> f32;
+		pub fn sin(x: f64) -> f64;
+		pub fn sinf(x: f32) -> f32;
+		pub fn cos(x: f64) -> f64;
+		pub fn cosf(x: f32) -> f32;
+		pub fn tan(x: f64) -> f64;
+		pub fn tanf(x: f32) -> f32;
+		pub fn asin(x: f64) -> f64;
+		pub fn asinf(x: f32) -> f32;
+		pub fn atan2(y: f64, x: f64) -> f64;
+		pub fn atan2f(y: f32, x: f32) -> f32;
+		pub fn hypot(x: f64, y: f64) -> f64;
+		pub fn hypotf(x: f32, y: f32) -> f32;
+		pub fn pow(x: f64, y: f64) -> f64;
+		pub fn powf(x: f32, y: f32) -> f32;
+	}
+}
+
+/// Floating point functions which are not available from `core`.
+pub trait FloatMath {
+	/// Returns the square root of a number.
+	fn sqrt(self) -> Self;
+	/// Returns the absolute value of a number.
+	fn abs(self) -> Self;
+	/// Returns the sine of a number in radians.
+	fn sin(self) -> Self;
+	/// Returns the cosine of a number in radians.
+	fn cos(self) -> Self;
+	/// Returns the tangent of a number in radians.
+	fn tan(self) -> Self;
+	/// Returns the arcsine of a number in radians, in the range of
+	/// `[-pi/2, pi/2]`.
+	fn asin(self) -> Self;
+	/// Returns the four quadrant arctangent of `self` (y) and `x` in radians,
+	/// in the range of `[-pi, pi]`.
+	fn atan2(self, x: Self) -> Self;
+	/// Returns the length of the hypotenuse of a right-angle triangle with
+	/// sides of length `self` and `y`, without overflowing for large values.
+	fn hypot(self, y: Self) -> Self;
+	/// Raises a number to a floating point power.
+	fn powf(self, n: Self) -> Self;
+}
+
+impl FloatMath for f64 {
+	#[inline]
+	fn sqrt(self) -> Self {
+		unsafe { ffi::sqrt(self) }
+	}
+
+	#[inline]
+	fn abs(self) -> Self {
+		unsafe { ffi::fabs(self) }
+	}
+
+	#[inline]
+	fn sin(self) -> Self {
+		unsafe { ffi::sin(self) }
+	}
+
+	#[inline]
+	fn cos(self) -> Self {
+		unsafe { ffi::cos(self) }
+	}
+
+	#[inline]
+	fn tan(self) -> Self {
+		unsafe { ffi::tan(self) }
+	}
+
+	#[inline]
+	fn asin(self) -> Self {
+		unsafe { ffi::asin(self) }
+	}
+
+	#[inline]
+	fn atan2(self, x: Self) -> Self {
+		unsafe { ffi::atan2(self, x) }
+	}
+
+	#[inline]
+	fn hypot(self, y: Self) -> Self {
+		unsafe { ffi::hypot(self, y) }
+	}
+
+	#[inline]
+	fn powf(self, n: Self) -> Self {
+		unsafe { ffi::pow(self, n) }
+	}
+}
+
+impl FloatMath for f32 {
+	#[inline]
+	fn sqrt(self) -> Self {
+		unsafe { ffi::sqrtf(self) }
+	}
+
+	#[inline]
+	fn abs(self) -> Self {
+		unsafe { ffi::fabsf(self) }
+	}
+
+	#[inline]
+	fn sin(self) -> Self {
+		unsafe { ffi::sinf(self) }
+	}
+
+	#[inline]
+	fn cos(self) -> Self {
+		unsafe { ffi::cosf(self) }
+	}
+
+	#[inline]
+	fn tan(self) -> Self {
+		unsafe { ffi::tanf(self) }
+	}
+
+	#[inline]
+	fn asin(self) -> Self {
+		unsafe { ffi::asinf(self) }
+	}
+
+	#[inline]
+	fn atan2(self, x: Self) -> Self {
+		unsafe { ffi::atan2f(self, x) }
+	}
+
+	#[inline]
+	fn hypot(self, y: Self) -> Self {
+		unsafe { ffi::hypotf(self, y) }
+	}
+
+	#[inline]
+	fn powf(self, n: Self) -> Self {
+		unsafe { ffi::powf(self, n) }
+	}
+}
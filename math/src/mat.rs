@@ -0,0 +1,150 @@
+//! Square matrices. The `Mat*` types are over `f32` and the `DMat*` types are
+//! over `f64`.
+
+use crate::vec::{DVec3, DVec4, Vec3, Vec4};
+// Tests link std, whose inherent float methods shadow these
+#[cfg(not(test))]
+use crate::FloatMath;
+
+use core::array;
+use core::fmt;
+use core::ops::{Mul, MulAssign};
+
+macro_rules! impl_mat {
+	($(#[$attr:meta])* $name:ident, $vec:ident, $t:ty, $n:literal) => {
+		$(#[$attr])*
+		///
+		/// The matrix is stored in column major order, so `cols[1].x` is the
+		/// element in the first row of the second column.
+		#[derive(Debug, Clone, Copy, PartialEq)]
+		pub struct $name {
+			pub cols: [$vec; $n],
+		}
+
+		impl $name {
+			/// A matrix with all elements set to zero.
+			pub const ZERO: Self = Self { cols: [$vec::ZERO; $n] };
+
+			/// Create a new matrix from its columns.
+			#[inline]
+			pub const fn from_cols(cols: [$vec; $n]) -> Self {
+				Self { cols }
+			}
+
+			/// Create a new identity matrix.
+			#[inline]
+			pub fn identity() -> Self {
+				Self {
+					cols: array::from_fn(|i| {
+						let mut col = [0.0; $n];
+						col[i] = 1.0;
+						$vec::from_array(col)
+					}),
+				}
+			}
+
+			/// Create a new matrix which rotates counter-clockwise about the Z
+			/// axis by `angle` radians.
+			#[inline]
+			pub fn from_rotation_z(angle: $t) -> Self {
+				let (sin, cos) = (angle.sin(), angle.cos());
+				let mut m = Self::identity();
+				m.cols[0].x = cos;
+				m.cols[0].y = sin;
+				m.cols[1].x = -sin;
+				m.cols[1].y = cos;
+				m
+			}
+
+			/// Get the transpose of this matrix, swapping its rows and columns.
+			#[inline]
+			pub fn transpose(self) -> Self {
+				let cols = self.cols.map($vec::to_array);
+				Self {
+					cols: array::from_fn(|i| $vec::from_array(array::from_fn(|j| cols[j][i]))),
+				}
+			}
+		}
+
+		impl Default for $name {
+			#[inline]
+			fn default() -> Self {
+				Self::identity()
+			}
+		}
+
+		impl Mul<$vec> for $name {
+			type Output = $vec;
+
+			#[inline]
+			fn mul(self, rhs: $vec) -> $vec {
+				let mut out = $vec::ZERO;
+				for (col, s) in self.cols.iter().zip(rhs.to_array()) {
+					out += *col * s;
+				}
+				out
+			}
+		}
+
+		impl Mul for $name {
+			type Output = $name;
+
+			#[inline]
+			fn mul(self, rhs: $name) -> $name {
+				Self {
+					cols: rhs.cols.map(|col| self * col),
+				}
+			}
+		}
+
+		impl MulAssign for $name {
+			#[inline]
+			fn mul_assign(&mut self, rhs: $name) {
+				*self = *self * rhs;
+			}
+		}
+
+		impl Mul<$t> for $name {
+			type Output = $name;
+
+			#[inline]
+			fn mul(self, rhs: $t) -> $name {
+				Self {
+					cols: self.cols.map(|col| col * rhs),
+				}
+			}
+		}
+
+		/// Formats as `[(row 1), (row 2), ...]`, any precision or width is
+		/// applied to each of the elements.
+		impl fmt::Display for $name {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				f.write_str("[")?;
+				for (i, row) in self.transpose().cols.iter().enumerate() {
+					if i != 0 {
+						f.write_str(", ")?;
+					}
+					fmt::Display::fmt(row, f)?;
+				}
+				f.write_str("]")
+			}
+		}
+	};
+}
+
+impl_mat!(
+	/// A 3x3 matrix of `f32`.
+	Mat3, Vec3, f32, 3
+);
+impl_mat!(
+	/// A 4x4 matrix of `f32`.
+	Mat4, Vec4, f32, 4
+);
+impl_mat!(
+	/// A 3x3 matrix of `f64`.
+	DMat3, DVec3, f64, 3
+);
+impl_mat!(
+	/// A 4x4 matrix of `f64`.
+	DMat4, DVec4, f64, 4
+);
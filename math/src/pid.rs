@@ -0,0 +1,109 @@
+//! A PID (proportional, integral, derivative) feedback controller.
+
+use core::time::Duration;
+
+/// A PID controller, which computes an output that drives an error towards
+/// zero.
+///
+/// The controller only works on the error itself, so the same controller can be
+/// used for anything from a flywheel's velocity to a lift's position. The gains
+/// are public so they can be tuned while the controller is running.
+///
+/// # Examples
+/// Driving a simulated first-order plant, such as a flywheel's velocity, to its
+/// setpoint. On a robot the output would be sent to a motor with
+/// `move_voltage()` instead.
+/// ```
+/// use core::time::Duration;
+/// use pros_math::pid::Pid;
+///
+/// let mut pid = Pid::new(2.0, 4.0, 0.0);
+/// pid.set_integral_limit(Some(200.0));
+///
+/// let target = 100.0;
+/// let dt = Duration::from_millis(10);
+/// // The plant settles towards the output with a time constant of 0.5s
+/// let rate = dt.as_secs_f64() / 0.5;
+/// let plant = |velocity: f64, output: f64| velocity + (output - velocity) * rate;
+/// let velocity = (0..1000).fold(0.0, |v, _| plant(v, pid.update(target - v, dt)));
+/// // The integral term removes any steady-state error
+/// assert!((target - velocity).abs() < 0.1);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Pid {
+	/// The proportional gain.
+	pub kp: f64,
+	/// The integral gain.
+	pub ki: f64,
+	/// The derivative gain.
+	pub kd: f64,
+	integral: f64,
+	integral_limit: Option<f64>,
+	last_error: Option<f64>,
+}
+
+impl Pid {
+	/// Create a new controller with the given gains and no integral limit.
+	#[inline]
+	pub const fn new(kp: f64, ki: f64, kd: f64) -> Self {
+		Pid {
+			kp,
+			ki,
+			kd,
+			integral: 0.0,
+			integral_limit: None,
+			last_error: None,
+		}
+	}
+
+	/// Limit the magnitude of the integral term's contribution to the output,
+	/// stopping it from winding up while the error can not be reduced, such as
+	/// when a mechanism is stalled. `None` removes the limit.
+	///
+	/// # Debug Assertions
+	/// Asserts that the limit is not negative.
+	#[inline]
+	pub fn set_integral_limit(&mut self, limit: Option<f64>) {
+		debug_assert!(limit.is_none_or(|l| l >= 0.0));
+		self.integral_limit = limit;
+		self.clamp_integral();
+	}
+
+	/// Compute the output of the controller from the current error and the
+	/// time `dt` since the previous update. The derivative term is zero on the
+	/// first update after creating or resetting the controller, or if `dt` is
+	/// zero.
+	pub fn update(&mut self, error: f64, dt: Duration) -> f64 {
+		let dt = dt.as_secs_f64();
+
+		self.integral += error * dt;
+		self.clamp_integral();
+
+		let derivative = match self.last_error {
+			Some(last) if dt > 0.0 => (error - last) / dt,
+			_ => 0.0,
+		};
+		self.last_error = Some(error);
+
+		self.kp * error + self.ki * self.integral + self.kd * derivative
+	}
+
+	/// Clear the accumulated integral and the previous error, such as when
+	/// moving to a new target.
+	#[inline]
+	pub fn reset(&mut self) {
+		self.integral = 0.0;
+		self.last_error = None;
+	}
+
+	fn clamp_integral(&mut self) {
+		// the limit is on the term's contribution to the output, rather than on
+		// the raw integral, so that it stays meaningful when ki is changed
+		if let Some(limit) = self.integral_limit {
+			if self.ki != 0.0 {
+				let max = limit / self.ki.abs();
+				self.integral = self.integral.clamp(-max, max);
+			}
+		}
+	}
+}
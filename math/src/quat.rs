@@ -0,0 +1,216 @@
+//! Quaternions for representing rotations in three dimensions.
+
+use crate::vec::DVec3;
+#[cfg(not(test))]
+use crate::FloatMath;
+
+use core::ops::{Mul, MulAssign};
+
+/// A quaternion of the form `w + xi + yj + zk`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quaternion {
+	pub w: f64,
+	pub x: f64,
+	pub y: f64,
+	pub z: f64,
+}
+
+impl Quaternion {
+	/// Create a new quaternion from its scalar and vector parts.
+	#[inline]
+	pub const fn new(w: f64, x: f64, y: f64, z: f64) -> Self {
+		Quaternion { w, x, y, z }
+	}
+
+	/// The identity quaternion, which represents no rotation.
+	#[inline]
+	pub const fn identity() -> Self {
+		Quaternion::new(1.0, 0.0, 0.0, 0.0)
+	}
+
+	/// Get the conjugate of this quaternion, for a normalised quaternion this
+	/// is the inverse rotation.
+	#[inline]
+	pub fn conjugate(self) -> Self {
+		Quaternion::new(self.w, -self.x, -self.y, -self.z)
+	}
+
+	/// Get the magnitude of this quaternion.
+	#[inline]
+	pub fn mag(self) -> f64 {
+		(self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+	}
+
+	/// Get this quaternion scaled to a magnitude of 1, as is required for it to
+	/// represent a rotation. A zero quaternion is returned as is.
+	#[inline]
+	pub fn normalise(self) -> Self {
+		let mag = self.mag();
+		if mag == 0.0 {
+			self
+		} else {
+			Quaternion::new(self.w / mag, self.x / mag, self.y / mag, self.z / mag)
+		}
+	}
+
+	/// Rotate a vector by the rotation this quaternion represents. The
+	/// quaternion is assumed to be normalised.
+	///
+	/// This computes `q * v * q'` without building the intermediate
+	/// quaternions.
+	#[inline]
+	pub fn rotate_vec3(self, v: DVec3) -> DVec3 {
+		let u = DVec3::new(self.x, self.y, self.z);
+		let t = 2.0 * u.cross(v);
+		v + self.w * t + u.cross(t)
+	}
+
+	/// Convert this rotation into Euler angles in radians, returned as
+	/// `(roll, pitch, yaw)`. The quaternion is assumed to be normalised.
+	///
+	/// The angles follow the right-handed Z-Y-X (yaw, pitch, roll) convention.
+	/// Roll is about the X axis, pitch about the Y axis and yaw about the Z
+	/// axis, each positive when rotating counter-clockwise while looking down
+	/// the axis towards the origin.
+	pub fn to_euler(self) -> (f64, f64, f64) {
+		let Quaternion { w, x, y, z } = self;
+
+		let roll = (2.0 * (w * x + y * z)).atan2(1.0 - 2.0 * (x * x + y * y));
+		// Rounding can push this just outside of the domain of asin near the
+		// poles, which would produce a NaN
+		let pitch = (2.0 * (w * y - z * x)).clamp(-1.0, 1.0).asin();
+		let yaw = (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z));
+
+		(roll, pitch, yaw)
+	}
+}
+
+impl From<mint::Quaternion<f64>> for Quaternion {
+	#[inline]
+	fn from(q: mint::Quaternion<f64>) -> Self {
+		Quaternion {
+			w: q.s,
+			x: q.v.x,
+			y: q.v.y,
+			z: q.v.z,
+		}
+	}
+}
+
+impl From<Quaternion> for mint::Quaternion<f64> {
+	#[inline]
+	fn from(q: Quaternion) -> Self {
+		mint::Quaternion {
+			s: q.w,
+			v: mint::Vector3 {
+				x: q.x,
+				y: q.y,
+				z: q.z,
+			},
+		}
+	}
+}
+
+/// The Hamilton product of two quaternions. The result applies the rotation of
+/// `rhs` first, followed by the rotation of `self`.
+impl Mul for Quaternion {
+	type Output = Quaternion;
+
+	#[inline]
+	fn mul(self, rhs: Quaternion) -> Quaternion {
+		Quaternion {
+			w: self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+			x: self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+			y: self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+			z: self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+		}
+	}
+}
+
+impl MulAssign for Quaternion {
+	#[inline]
+	fn mul_assign(&mut self, rhs: Quaternion) {
+		*self = *self * rhs;
+	}
+}
+
+impl Default for Quaternion {
+	#[inline]
+	fn default() -> Self {
+		Quaternion::identity()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::angle::angle_wrap_rad;
+	use core::f64::consts::{FRAC_1_SQRT_2, FRAC_PI_2};
+
+	const EPSILON: f64 = 1e-9;
+
+	fn assert_euler(q: Quaternion, expected: (f64, f64, f64)) {
+		let (roll, pitch, yaw) = q.to_euler();
+		assert!((roll - expected.0).abs() < EPSILON, "roll was {roll}");
+		assert!((pitch - expected.1).abs() < EPSILON, "pitch was {pitch}");
+		assert!((yaw - expected.2).abs() < EPSILON, "yaw was {yaw}");
+	}
+
+	fn assert_vec3(v: DVec3, expected: DVec3) {
+		assert!((v - expected).mag() < EPSILON, "{v:?} != {expected:?}");
+	}
+
+	#[test]
+	fn identity_to_euler() {
+		assert_euler(Quaternion::identity(), (0.0, 0.0, 0.0));
+	}
+
+	#[test]
+	fn yaw_to_euler() {
+		let q = Quaternion::new(FRAC_1_SQRT_2, 0.0, 0.0, FRAC_1_SQRT_2);
+		assert_euler(q, (0.0, 0.0, FRAC_PI_2));
+		// Counter-clockwise about Z turns X into Y
+		assert_vec3(
+			q.rotate_vec3(DVec3::new(1.0, 0.0, 0.0)),
+			DVec3::new(0.0, 1.0, 0.0),
+		);
+	}
+
+	#[test]
+	fn pitch_to_euler() {
+		let q = Quaternion::new(FRAC_1_SQRT_2, 0.0, FRAC_1_SQRT_2, 0.0);
+		let (roll, pitch, yaw) = q.to_euler();
+		assert!((pitch - FRAC_PI_2).abs() < EPSILON, "pitch was {pitch}");
+		// Roll and yaw are about the same axis at the pole, so only their
+		// difference is meaningful
+		assert!(
+			angle_wrap_rad(roll - yaw).abs() < EPSILON,
+			"roll was {roll}, yaw was {yaw}"
+		);
+		// Counter-clockwise about Y turns Z into X
+		assert_vec3(
+			q.rotate_vec3(DVec3::new(0.0, 0.0, 1.0)),
+			DVec3::new(1.0, 0.0, 0.0),
+		);
+	}
+
+	#[test]
+	fn roll_to_euler() {
+		let q = Quaternion::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0, 0.0);
+		assert_euler(q, (FRAC_PI_2, 0.0, 0.0));
+		// Counter-clockwise about X turns Y into Z
+		assert_vec3(
+			q.rotate_vec3(DVec3::new(0.0, 1.0, 0.0)),
+			DVec3::new(0.0, 0.0, 1.0),
+		);
+	}
+
+	#[test]
+	fn product_matches_rotate_vec3() {
+		let a = Quaternion::new(0.5, 0.5, -0.5, 0.5);
+		let b = Quaternion::new(0.9, 0.1, 0.3, -0.2).normalise();
+		let v = DVec3::new(1.0, -2.0, 3.0);
+		// The product applies the rotation of `b` first, then `a`
+		assert_vec3((a * b).rotate_vec3(v), a.rotate_vec3(b.rotate_vec3(v)));
+	}
+}
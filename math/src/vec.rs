@@ -0,0 +1,267 @@
+//! Fixed size vectors. The `Vec*` types are over `f32` and the `DVec*` types
+//! are over `f64`.
+//!
+//! Each vector converts to and from the [`mint`] vector of the same size, which
+//! is what the devices in `pros-rs` return.
+
+// Tests link std, whose inherent float methods shadow these
+#[cfg(not(test))]
+use crate::FloatMath;
+
+use core::fmt;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+macro_rules! impl_vec {
+	($(#[$attr:meta])* $name:ident, $mint:ident, $t:ty, $n:literal, $($field:ident),+) => {
+		$(#[$attr])*
+		#[derive(Debug, Default, Clone, Copy, PartialEq)]
+		pub struct $name {
+			$(pub $field: $t),+
+		}
+
+		impl $name {
+			/// A vector with all components set to zero.
+			pub const ZERO: Self = Self { $($field: 0.0),+ };
+
+			/// Create a new vector from its components.
+			#[inline]
+			pub const fn new($($field: $t),+) -> Self {
+				Self { $($field),+ }
+			}
+
+			/// Create a new vector with all components set to `v`.
+			#[inline]
+			pub const fn splat(v: $t) -> Self {
+				Self { $($field: v),+ }
+			}
+
+			/// Create a new vector from an array of its components.
+			#[inline]
+			pub const fn from_array(a: [$t; $n]) -> Self {
+				let [$($field),+] = a;
+				Self { $($field),+ }
+			}
+
+			/// Get the components of this vector as an array.
+			#[inline]
+			pub const fn to_array(self) -> [$t; $n] {
+				[$(self.$field),+]
+			}
+
+			/// Get the dot product of two vectors.
+			#[inline]
+			pub fn dot(self, rhs: Self) -> $t {
+				0.0 $(+ self.$field * rhs.$field)+
+			}
+
+			/// Get the squared magnitude of this vector, this avoids the
+			/// square root needed by [`Self::mag()`].
+			#[inline]
+			pub fn mag_squared(self) -> $t {
+				self.dot(self)
+			}
+
+			/// Get the magnitude of this vector.
+			#[inline]
+			pub fn mag(self) -> $t {
+				self.mag_squared().sqrt()
+			}
+
+			/// Get the distance between the points described by two vectors.
+			#[inline]
+			pub fn distance(self, rhs: Self) -> $t {
+				(self - rhs).mag()
+			}
+
+			/// Linearly interpolate between `self` and `rhs`, where a `t` of
+			/// 0 gives `self` and a `t` of 1 gives `rhs`. Values of `t`
+			/// outside of `[0, 1]` extrapolate past either end.
+			#[inline]
+			pub fn lerp(self, rhs: Self, t: $t) -> Self {
+				self + (rhs - self) * t
+			}
+
+			/// Get a vector in the same direction as this one with a magnitude
+			/// of 1. The zero vector has no direction and is returned as is.
+			#[inline]
+			pub fn normalise(self) -> Self {
+				let mag = self.mag();
+				if mag == 0.0 {
+					self
+				} else {
+					self / mag
+				}
+			}
+		}
+
+		impl Add for $name {
+			type Output = $name;
+
+			#[inline]
+			fn add(self, rhs: $name) -> $name {
+				Self { $($field: self.$field + rhs.$field),+ }
+			}
+		}
+
+		impl AddAssign for $name {
+			#[inline]
+			fn add_assign(&mut self, rhs: $name) {
+				*self = *self + rhs;
+			}
+		}
+
+		impl Sub for $name {
+			type Output = $name;
+
+			#[inline]
+			fn sub(self, rhs: $name) -> $name {
+				Self { $($field: self.$field - rhs.$field),+ }
+			}
+		}
+
+		impl SubAssign for $name {
+			#[inline]
+			fn sub_assign(&mut self, rhs: $name) {
+				*self = *self - rhs;
+			}
+		}
+
+		impl Mul<$t> for $name {
+			type Output = $name;
+
+			#[inline]
+			fn mul(self, rhs: $t) -> $name {
+				Self { $($field: self.$field * rhs),+ }
+			}
+		}
+
+		impl Mul<$name> for $t {
+			type Output = $name;
+
+			#[inline]
+			fn mul(self, rhs: $name) -> $name {
+				rhs * self
+			}
+		}
+
+		impl MulAssign<$t> for $name {
+			#[inline]
+			fn mul_assign(&mut self, rhs: $t) {
+				*self = *self * rhs;
+			}
+		}
+
+		impl Div<$t> for $name {
+			type Output = $name;
+
+			#[inline]
+			fn div(self, rhs: $t) -> $name {
+				Self { $($field: self.$field / rhs),+ }
+			}
+		}
+
+		impl DivAssign<$t> for $name {
+			#[inline]
+			fn div_assign(&mut self, rhs: $t) {
+				*self = *self / rhs;
+			}
+		}
+
+		impl Neg for $name {
+			type Output = $name;
+
+			#[inline]
+			fn neg(self) -> $name {
+				Self { $($field: -self.$field),+ }
+			}
+		}
+
+		impl From<[$t; $n]> for $name {
+			#[inline]
+			fn from(a: [$t; $n]) -> Self {
+				Self::from_array(a)
+			}
+		}
+
+		impl From<$name> for [$t; $n] {
+			#[inline]
+			fn from(v: $name) -> Self {
+				v.to_array()
+			}
+		}
+
+		impl From<mint::$mint<$t>> for $name {
+			#[inline]
+			fn from(v: mint::$mint<$t>) -> Self {
+				Self { $($field: v.$field),+ }
+			}
+		}
+
+		impl From<$name> for mint::$mint<$t> {
+			#[inline]
+			fn from(v: $name) -> Self {
+				Self { $($field: v.$field),+ }
+			}
+		}
+
+		/// Formats as `(x, y, ...)`, any precision or width is applied to each
+		/// of the components.
+		impl fmt::Display for $name {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				f.write_str("(")?;
+				for (i, v) in self.to_array().iter().enumerate() {
+					if i != 0 {
+						f.write_str(", ")?;
+					}
+					fmt::Display::fmt(v, f)?;
+				}
+				f.write_str(")")
+			}
+		}
+	};
+}
+
+macro_rules! impl_cross {
+	($name:ident) => {
+		impl $name {
+			/// Get the cross product of two vectors, this is perpendicular to
+			/// both following the right-hand rule.
+			#[inline]
+			pub fn cross(self, rhs: Self) -> Self {
+				Self {
+					x: self.y * rhs.z - self.z * rhs.y,
+					y: self.z * rhs.x - self.x * rhs.z,
+					z: self.x * rhs.y - self.y * rhs.x,
+				}
+			}
+		}
+	};
+}
+
+impl_vec!(
+	/// A two component vector of `f32`.
+	Vec2, Vector2, f32, 2, x, y
+);
+impl_vec!(
+	/// A three component vector of `f32`.
+	Vec3, Vector3, f32, 3, x, y, z
+);
+impl_vec!(
+	/// A four component vector of `f32`.
+	Vec4, Vector4, f32, 4, x, y, z, w
+);
+impl_vec!(
+	/// A two component vector of `f64`.
+	DVec2, Vector2, f64, 2, x, y
+);
+impl_vec!(
+	/// A three component vector of `f64`.
+	DVec3, Vector3, f64, 3, x, y, z
+);
+impl_vec!(
+	/// A four component vector of `f64`.
+	DVec4, Vector4, f64, 4, x, y, z, w
+);
+
+impl_cross!(Vec3);
+impl_cross!(DVec3);
@@ -0,0 +1,115 @@
+//! Legacy 3-wire sensors which take up a pair of TriPorts.
+//!
+//! Both of the TriPorts must be on the same expander (or both be internal
+//! TriPorts), and must be next to each other with the first one on an odd
+//! port, such as A and B or C and D.
+
+use crate::bindings::*;
+use crate::devices::{DeviceError, Direction};
+use crate::ports::TriPort;
+
+/// A legacy quadrature encoder, such as the red Optical Shaft Encoder.
+#[derive(Debug)]
+pub struct AdiEncoder {
+	_top: TriPort,
+	_bottom: TriPort,
+	encoder: ext_adi_encoder_t,
+}
+
+impl AdiEncoder {
+	/// Create a new encoder from the TriPorts its two wires are plugged into,
+	/// the encoder's count starts from zero.
+	///
+	/// # Safety
+	/// There must only ever be a single reference to these TriPorts. It is up
+	/// to the caller to make sure there does not exist another device object
+	/// using the same TriPorts.
+	pub unsafe fn new(
+		top: TriPort,
+		bottom: TriPort,
+		direction: Direction,
+	) -> Result<Self, DeviceError> {
+		if top.ext_port.get() != bottom.ext_port.get() {
+			return Err(DeviceError::PortRange);
+		}
+		let encoder = pros_unsafe_err!(
+			ext_adi_encoder_init,
+			err = DeviceError::errno_adi(),
+			top.ext_port.get(),
+			top.port.get(),
+			bottom.port.get(),
+			direction == Direction::Reverse
+		)?;
+		Ok(AdiEncoder {
+			_top: top,
+			_bottom: bottom,
+			encoder,
+		})
+	}
+
+	/// Get the amount of ticks the encoder has counted since it was created or
+	/// last reset. There are 360 ticks in one rotation.
+	pub fn get(&self) -> Result<i32, DeviceError> {
+		pros_unsafe_err!(
+			ext_adi_encoder_get,
+			err = DeviceError::errno_adi(),
+			self.encoder
+		)
+	}
+
+	/// Reset the encoder's count back to zero.
+	pub fn reset(&mut self) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			ext_adi_encoder_reset,
+			err = DeviceError::errno_adi(),
+			self.encoder
+		)?;
+		Ok(())
+	}
+}
+
+/// A legacy ultrasonic rangefinder.
+#[derive(Debug)]
+pub struct AdiUltrasonic {
+	_ping: TriPort,
+	_echo: TriPort,
+	ultrasonic: ext_adi_ultrasonic_t,
+}
+
+impl AdiUltrasonic {
+	/// Create a new ultrasonic sensor from the TriPorts its output (orange)
+	/// and input (yellow) wires are plugged into.
+	///
+	/// # Safety
+	/// There must only ever be a single reference to these TriPorts. It is up
+	/// to the caller to make sure there does not exist another device object
+	/// using the same TriPorts.
+	pub unsafe fn new(ping: TriPort, echo: TriPort) -> Result<Self, DeviceError> {
+		if ping.ext_port.get() != echo.ext_port.get() {
+			return Err(DeviceError::PortRange);
+		}
+		let ultrasonic = pros_unsafe_err!(
+			ext_adi_ultrasonic_init,
+			err = DeviceError::errno_adi(),
+			ping.ext_port.get(),
+			ping.port.get(),
+			echo.port.get()
+		)?;
+		Ok(AdiUltrasonic {
+			_ping: ping,
+			_echo: echo,
+			ultrasonic,
+		})
+	}
+
+	/// Get the distance to the nearest object in front of the sensor in
+	/// tenths of a millimetre, so `10000` is one metre. If there is no object
+	/// in range `0` is returned.
+	pub fn get_distance(&self) -> Result<i32, DeviceError> {
+		pros_unsafe_err!(
+			ext_adi_ultrasonic_get,
+			err = DeviceError::errno_adi(),
+			self.ultrasonic
+		)
+	}
+}
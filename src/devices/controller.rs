@@ -3,17 +3,30 @@ use smallvec::SmallVec;
 
 use crate::bindings::*;
 use crate::devices::DeviceError;
-use crate::util::to_cstring;
-
-use alloc::string::String;
+use crate::math::FloatMath;
+use crate::rtos::{
+	action::{Action, NextSleep, Poll},
+	tasks::Task,
+	time::Instant,
+};
+use crate::util::StackString;
+
+use alloc::boxed::Box;
+use core::fmt::{self, Write};
+use core::time::Duration;
 
 /// A reference to a certain connected controller.
 #[derive(Debug)]
 pub struct Controller {
 	id: u32,
+	last_write: Option<Instant>,
 }
 
 impl Controller {
+	/// The controller only accepts an update to its display about once every
+	/// 50ms, any writes sent faster than this are discarded.
+	pub const WRITE_PERIOD: Duration = Duration::from_millis(50);
+
 	/// Get a reference to the master controller on the V5 Brain.
 	///
 	/// # Safety
@@ -23,6 +36,7 @@ impl Controller {
 	pub unsafe fn master() -> Controller {
 		Controller {
 			id: controller_id_e_t_E_CONTROLLER_MASTER,
+			last_write: None,
 		}
 	}
 
@@ -35,6 +49,7 @@ impl Controller {
 	pub unsafe fn slave() -> Controller {
 		Controller {
 			id: controller_id_e_t_E_CONTROLLER_PARTNER,
+			last_write: None,
 		}
 	}
 
@@ -44,7 +59,7 @@ impl Controller {
 	pub fn get_analog_raw(&self, axis: Axis) -> Result<i8, DeviceError> {
 		let res = pros_unsafe_err!(
 			controller_get_analog,
-			err = DeviceError::errno_generic(),
+			err = self.error(),
 			self.id,
 			axis.into()
 		)?;
@@ -57,6 +72,35 @@ impl Controller {
 			.map(|i| (i as f64 / 127.0).clamp(-1.0, 1.0))
 	}
 
+	/// Gets the value of an analog axis (joystick) on a controller with a
+	/// deadband and response curve applied.
+	///
+	/// Inputs with a magnitude under `deadband` are read as zero, which stops
+	/// the robot from creeping when a joystick does not quite return to its
+	/// centre. The rest of the range is rescaled to start from zero and raised
+	/// to `exponent`, keeping its sign, so an exponent above 1 gives finer
+	/// control at low speeds while still reaching full speed. A typical setup
+	/// is a deadband of `0.05` and an exponent of `2.0`.
+	///
+	/// # Debug Assertions
+	/// Asserts that `deadband` is within `[0, 1)` and `exponent` is positive.
+	pub fn get_analog_curved(
+		&self,
+		axis: Axis,
+		deadband: f64,
+		exponent: f64,
+	) -> Result<f64, DeviceError> {
+		debug_assert!((0.0..1.0).contains(&deadband));
+		debug_assert!(exponent > 0.0);
+		let value = self.get_analog(axis)?;
+		let mag = value.abs();
+		if mag < deadband {
+			return Ok(0.0);
+		}
+		let scaled = ((mag - deadband) / (1.0 - deadband)).powf(exponent);
+		Ok(if value < 0.0 { -scaled } else { scaled })
+	}
+
 	/// Get the value of a digital axis (button) on a controller. If the axis is
 	/// high a `true` boolean is return, likewise if it low a `false` is
 	/// returned.
@@ -66,7 +110,7 @@ impl Controller {
 		for button in Buttons::all().iter() {
 			if pros_unsafe_err_bool!(
 				controller_get_digital,
-				err = DeviceError::errno_generic(),
+				err = self.error(),
 				self.id,
 				Buttons::to_pros(button)
 			)? {
@@ -87,7 +131,7 @@ impl Controller {
 		for button in Buttons::all().iter() {
 			if pros_unsafe_err_bool!(
 				controller_get_digital_new_press,
-				err = DeviceError::errno_generic(),
+				err = self.error(),
 				self.id,
 				Buttons::to_pros(button)
 			)? {
@@ -98,22 +142,35 @@ impl Controller {
 		Ok(pressed)
 	}
 
-	/// Gets the battery capacity for the given controller.
+	/// Gets the remaining charge of the controller's battery as a percentage.
+	/// Prefer [`Controller::battery_percent()`], which makes sure this is
+	/// within `[0, 100]`.
+	///
+	/// The controller only reports its battery occasionally, so this can lag
+	/// behind the real charge by several seconds.
 	pub fn battery_capacity(&self) -> Result<i32, DeviceError> {
-		pros_unsafe_err!(
-			controller_get_battery_capacity,
-			err = DeviceError::errno_generic(),
-			self.id
-		)
+		pros_unsafe_err!(controller_get_battery_capacity, err = self.error(), self.id)
 	}
 
-	/// Gets the battery level for the given controller.
+	/// Gets the raw battery level reported by the controller. This is not
+	/// scaled to any particular unit, use [`Controller::battery_percent()`]
+	/// for the remaining charge.
 	pub fn battery_level(&self) -> Result<i32, DeviceError> {
-		pros_unsafe_err!(
-			controller_get_battery_level,
-			err = DeviceError::errno_generic(),
-			self.id
-		)
+		pros_unsafe_err!(controller_get_battery_level, err = self.error(), self.id)
+	}
+
+	/// Gets the remaining charge of the controller's battery as a percentage,
+	/// within `[0, 100]`. Like [`Controller::battery_capacity()`] this only
+	/// updates every few seconds, so is best used for a low battery warning.
+	///
+	/// # Examples
+	/// ```
+	/// if controller.battery_percent()? < 20.0 {
+	/// 	controller.set_text(2, 0, "LOW BATTERY");
+	/// }
+	/// ```
+	pub fn battery_percent(&self) -> Result<f64, DeviceError> {
+		Ok((self.battery_capacity()? as f64).clamp(0.0, 100.0))
 	}
 
 	/// Tests to see if this controller is currently connected or not.
@@ -125,14 +182,112 @@ impl Controller {
 		)
 	}
 
+	/// Check if this controller is there to be used. Unlike
+	/// [`Controller::is_connected()`] this never returns an error, if the
+	/// connection can not be checked the controller is treated as not being
+	/// there.
+	///
+	/// A handle to the partner controller is always available from
+	/// [`Devices`][crate::devices::Devices] whether or not one is actually
+	/// connected, so check this before relying on it.
+	///
+	/// # Examples
+	/// ```
+	/// let partner = devices.take_slave_controller();
+	/// if !partner.exists() {
+	/// 	println!("no partner controller, driving solo");
+	/// }
+	/// ```
+	pub fn exists(&self) -> bool {
+		unsafe { controller_is_connected(self.id) == 1 }
+	}
+
+	/// The error for a call to this controller which failed. A controller which
+	/// is not connected is reported as such rather than as whatever PROS set
+	/// errno to.
+	fn error(&self) -> DeviceError {
+		if self.exists() {
+			DeviceError::errno_generic()
+		} else {
+			DeviceError::ControllerNotConnected
+		}
+	}
+
 	/// Sets a segments of characters on the controller display to a value. A
 	/// line and column for the cursor must also be supplied. Any text that does
 	/// not fit onto the screen is truncated and discarded.
 	pub fn set_text(&mut self, line: u8, column: u8, text: &str) {
-		let cstring = to_cstring(String::from(text));
+		// The display is far smaller than this, so truncating doesn't lose
+		// anything that would have been shown
+		let mut buf = StackString::<32>::new();
+		let _ = buf.write_str(text);
 		unsafe {
-			controller_set_text(self.id, line, column, cstring.as_ptr() as _);
+			controller_set_text(self.id, line, column, buf.as_c_str().as_ptr());
+		}
+	}
+
+	/// Prints text onto the controller display at the given line and column,
+	/// without clearing anything else on the display. Any text that does not
+	/// fit onto the screen is truncated and discarded.
+	///
+	/// The controller only accepts a display update about once every
+	/// [`Controller::WRITE_PERIOD`]. To avoid this text being silently
+	/// dropped, if the previous write from this handle was too recent the
+	/// current task is delayed until the controller is ready again.
+	///
+	/// # Examples
+	/// ```
+	/// use core::fmt::Write;
+	///
+	/// let mut text = StackString::<32>::new();
+	/// loop {
+	/// 	text.clear();
+	/// 	let _ = write!(text, "battery: {:3}%", Battery::get_capacity()?);
+	/// 	controller.print(0, 0, &text)?;
+	/// }
+	/// ```
+	pub fn print(&mut self, line: u8, column: u8, text: &str) -> Result<(), DeviceError> {
+		// The display is far smaller than this, so truncating doesn't lose
+		// anything that would have been shown
+		let mut buf = StackString::<32>::new();
+		let _ = buf.write_str(text);
+		self.wait_for_write();
+		pros_unsafe_err!(
+			controller_print,
+			err = self.error(),
+			self.id,
+			line,
+			column,
+			cstr!("%s"),
+			buf.as_c_str().as_ptr()
+		)?;
+		Ok(())
+	}
+
+	/// Delay the current task until [`Controller::WRITE_PERIOD`] has passed
+	/// since the last write to the display, then mark the start of a new write.
+	fn wait_for_write(&mut self) {
+		if let Some(last) = self.last_write {
+			let elapsed = last.elapsed();
+			if elapsed < Self::WRITE_PERIOD {
+				Task::delay(Self::WRITE_PERIOD - elapsed);
+			}
 		}
+		self.last_write = Some(Instant::now());
+	}
+
+	/// Formats text and sets it on the controller display in the same way as
+	/// [`Controller::set_text()`]. The text is formatted into a buffer on the
+	/// stack, so this does not allocate.
+	///
+	/// # Examples
+	/// ```
+	/// controller.set_text_fmt(0, 0, format_args!("battery: {}%", capacity));
+	/// ```
+	pub fn set_text_fmt(&mut self, line: u8, column: u8, args: fmt::Arguments) {
+		let mut buf = StackString::<32>::new();
+		let _ = buf.write_fmt(args);
+		self.set_text(line, column, &buf);
 	}
 
 	/// Clear the entire character display on the controller.
@@ -202,6 +357,91 @@ impl From<Axis> for controller_analog_e_t {
 	}
 }
 
+/// Calls closures when buttons on a controller are pressed or released.
+///
+/// The controller is read each time [`ButtonListener::poll()`] is called, which
+/// compares which buttons are held against the previous poll. This should be
+/// called once per loop in opcontrol. Buttons which are already held on the
+/// first poll count as being pressed.
+///
+/// # Examples
+/// ```
+/// // Both closures need the intake, so share it behind a mutex
+/// let intake = Mutex::new(intake);
+/// let mut listener = ButtonListener::new(&controller);
+/// listener
+/// 	.on_press(Buttons::R1, || {
+/// 		let _ = intake.lock().move_voltage(12000);
+/// 	})
+/// 	.on_release(Buttons::R1, || {
+/// 		let _ = intake.lock().move_voltage(0);
+/// 	});
+/// loop {
+/// 	listener.poll()?;
+/// 	Task::delay(Duration::from_millis(10));
+/// }
+/// ```
+pub struct ButtonListener<'a> {
+	controller: &'a Controller,
+	last: Buttons,
+	handlers: SmallVec<[ButtonHandler<'a>; 8]>,
+}
+
+struct ButtonHandler<'a> {
+	buttons: Buttons,
+	on_press: bool,
+	f: Box<dyn FnMut() + 'a>,
+}
+
+impl<'a> ButtonListener<'a> {
+	/// Create a new listener for `controller` with no closures registered.
+	pub fn new(controller: &'a Controller) -> Self {
+		ButtonListener {
+			controller,
+			last: Buttons::empty(),
+			handlers: SmallVec::new(),
+		}
+	}
+
+	/// Call `f` whenever any of `buttons` goes from released to pressed.
+	pub fn on_press(&mut self, buttons: Buttons, f: impl FnMut() + 'a) -> &mut Self {
+		self.handlers.push(ButtonHandler {
+			buttons,
+			on_press: true,
+			f: Box::new(f),
+		});
+		self
+	}
+
+	/// Call `f` whenever any of `buttons` goes from pressed to released.
+	pub fn on_release(&mut self, buttons: Buttons, f: impl FnMut() + 'a) -> &mut Self {
+		self.handlers.push(ButtonHandler {
+			buttons,
+			on_press: false,
+			f: Box::new(f),
+		});
+		self
+	}
+
+	/// Read the controller and call the closures for any buttons which have
+	/// been pressed or released since the last poll. Closures are called in
+	/// the order they were registered, each at most once per poll.
+	pub fn poll(&mut self) -> Result<(), DeviceError> {
+		let current = self.controller.get_buttons()?;
+		let pressed = current & !self.last;
+		let released = self.last & !current;
+		self.last = current;
+
+		for handler in self.handlers.iter_mut() {
+			let edges = if handler.on_press { pressed } else { released };
+			if edges.intersects(handler.buttons) {
+				(handler.f)();
+			}
+		}
+		Ok(())
+	}
+}
+
 bitflags::bitflags! {
 	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 	pub struct Buttons: u16 {
@@ -249,6 +489,10 @@ impl Buttons {
 pub struct Battery;
 
 impl Battery {
+	/// The capacity as a percentage below which the battery is considered low
+	/// by [`Battery::is_low()`].
+	pub const LOW_CAPACITY: f64 = 20.0;
+
 	/// Get the current capacity of the battery.
 	pub fn get_capacity() -> Result<f64, DeviceError> {
 		pros_unsafe_err_f!(battery_get_capacity, err = DeviceError::errno_generic())
@@ -270,10 +514,49 @@ impl Battery {
 	pub fn get_voltage() -> Result<i32, DeviceError> {
 		pros_unsafe_err!(battery_get_voltage, err = DeviceError::errno_generic())
 	}
+
+	/// Get the capacity of the battery as a percentage, clamped to be within
+	/// 0 to 100.
+	pub fn percent() -> Result<f64, DeviceError> {
+		Self::get_capacity().map(|c| c.clamp(0.0, 100.0))
+	}
+
+	/// Returns `true` if the capacity of the battery is below
+	/// [`Battery::LOW_CAPACITY`].
+	pub fn is_low() -> Result<bool, DeviceError> {
+		Self::get_capacity().map(|c| c < Self::LOW_CAPACITY)
+	}
+
+	/// Get all of the readings of the battery at once.
+	pub fn status() -> Result<BatteryStatus, DeviceError> {
+		Ok(BatteryStatus {
+			capacity: Self::get_capacity()?,
+			voltage: Self::get_voltage()?,
+			current: Self::get_current()?,
+			temperature: Self::get_temperature()?,
+		})
+	}
+}
+
+/// All of the readings of the battery, as returned by [`Battery::status()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatteryStatus {
+	/// The capacity of the battery as a percentage,
+	pub capacity: f64,
+	/// The voltage of the battery in millivolts,
+	pub voltage: i32,
+	/// The current being drawn from the battery in milliamps,
+	pub current: i32,
+	/// The temperature of the battery in degrees Celsius,
+	pub temperature: f64,
 }
 
+/// How often to check the competition state when waiting for it to change.
+const COMPETITION_POLL_PERIOD: Duration = Duration::from_millis(10);
+
 bitflags! {
 	/// Bitflags for defining the state of the robot in competition mode.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 	pub struct CompetitionMode: u8 {
 		/// The brain is disabled.
 		const DISABLED = 0x1 << 0x0;
@@ -305,4 +588,41 @@ impl CompetitionMode {
 	pub fn is_connected() -> bool {
 		Self::get_status().contains(CompetitionMode::CONNECTED)
 	}
+
+	/// Returns an action which will complete once the competition state of the
+	/// V5 Brain is different to `previous`, containing the new state.
+	///
+	/// # Examples
+	/// ```
+	/// let mut change = CompetitionMode::wait_for_change(CompetitionMode::get_status());
+	/// loop {
+	/// 	// main opcontrol code
+	/// 	action! {
+	/// 		_ = change => break,
+	/// 		_ = timer.action() => continue
+	/// 	}
+	/// }
+	/// ```
+	pub fn wait_for_change(previous: CompetitionMode) -> impl Action<Output = CompetitionMode> {
+		struct ChangeAction(CompetitionMode);
+
+		impl Action for ChangeAction {
+			type Output = CompetitionMode;
+
+			fn poll(&mut self) -> Poll<Self::Output> {
+				let status = CompetitionMode::get_status();
+				if status != self.0 {
+					Poll::Complete(status)
+				} else {
+					Poll::Waiting
+				}
+			}
+
+			fn next(&mut self) -> NextSleep {
+				NextSleep::Timestamp(COMPETITION_POLL_PERIOD)
+			}
+		}
+
+		ChangeAction(previous)
+	}
 }
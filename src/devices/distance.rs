@@ -43,4 +43,14 @@ impl DistanceSensor {
 			self.get_port()
 		)
 	}
+
+	/// Get the velocity of the detected object relative to the sensor in
+	/// meters per second.
+	pub fn get_object_velocity(&self) -> Result<f64, DeviceError> {
+		pros_unsafe_err_f!(
+			distance_get_object_velocity,
+			err = DeviceError::errno_distance(),
+			self.get_port()
+		)
+	}
 }
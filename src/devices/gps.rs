@@ -1,14 +1,23 @@
 use crate::bindings::*;
 use crate::devices::DeviceError;
+use crate::math::angle::angle_norm_deg;
 use crate::ports::Port;
+use crate::rtos::action::{Action, NextSleep, Poll};
+use crate::rtos::time::Instant;
 use crate::util::PROS_ERR_F;
 
+use core::time::Duration;
 use mint::{Vector2, Vector3};
 
+/// The data rate of the GPS sensor until it is changed with
+/// [`GPS::set_data_rate()`].
+const DEFAULT_DATA_RATE: Duration = Duration::from_millis(20);
+
 /// A struct which holds and presents a connected Game Positioning System
 /// connected to the V5 Brain.
 pub struct GPS {
 	pub port: Port,
+	data_rate: Duration,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -31,7 +40,10 @@ impl GPS {
 	/// the same port. If there is another device object with the same port this
 	/// will result in undefined behaviour and/or panics.
 	pub unsafe fn new(port: Port) -> Result<Self, DeviceError> {
-		let gps = GPS { port };
+		let gps = GPS {
+			port,
+			data_rate: DEFAULT_DATA_RATE,
+		};
 		Ok(gps)
 	}
 
@@ -64,6 +76,10 @@ impl GPS {
 
 	/// Set the GPS's sensor location offset relative to the centre of the
 	/// robot's turning point in meters.
+	///
+	/// The sensor applies this offset itself, so once it is set the positions
+	/// returned by [`GPS::get_state()`] are of the robot's turning point
+	/// rather than of the sensor.
 	pub fn set_offset(&mut self, offset: Vector2<f64>) -> Result<(), DeviceError> {
 		pros_unsafe_err!(
 			gps_set_offset,
@@ -75,6 +91,20 @@ impl GPS {
 		Ok(())
 	}
 
+	/// Read back the GPS's sensor location offset in meters, as it is
+	/// currently set on the sensor itself.
+	pub fn get_offset(&self) -> Result<Vector2<f64>, DeviceError> {
+		let (mut x, mut y) = (0.0, 0.0);
+		pros_unsafe_err!(
+			gps_get_offset,
+			err = DeviceError::errno_imu(),
+			self.get_port(),
+			&mut x,
+			&mut y
+		)?;
+		Ok([x, y].into())
+	}
+
 	/// Set the robot's location relative to the centre of the field in meters.
 	/// Position is the offset from centre of the field which is marked at (0,
 	/// 0). The heading of the robot is also set in degrees.
@@ -115,9 +145,17 @@ impl GPS {
 			self.get_port(),
 			rate
 		)?;
+		self.data_rate = Duration::from_millis((rate / 5 * 5).max(5) as u64);
 		Ok(())
 	}
 
+	/// Get the data rate of the GPS sensor, as last set by
+	/// [`GPS::set_data_rate()`].
+	#[inline]
+	pub fn get_data_rate(&self) -> Duration {
+		self.data_rate
+	}
+
 	/// Get the possible RMS (Root Mean Squared) error in meters for the GPS
 	/// position.
 	pub fn get_error(&self) -> Result<f64, DeviceError> {
@@ -144,6 +182,58 @@ impl GPS {
 		}
 	}
 
+	/// Get the position of the centre of the robot on the field in meters
+	/// along with its heading in degrees, within [0, 360).
+	///
+	/// The sensor already removes the offset from [`GPS::set_offset()`] or
+	/// [`GPS::initialise()`] from the position it reports, so this is the
+	/// position from [`GPS::get_state()`] as is. The heading is taken from the
+	/// yaw of the same reading, so the position and heading always come from
+	/// the same sample.
+	pub fn robot_pose(&self) -> Result<(Vector2<f64>, f64), DeviceError> {
+		let state = self.get_state()?;
+		Ok((state.position, angle_norm_deg(state.yaw)))
+	}
+
+	/// Returns an action which will complete with the state of the GPS sensor
+	/// once the sensor has had time to produce a new reading, based on its
+	/// data rate. This avoids reading the same value more than once.
+	///
+	/// # Examples
+	/// ```
+	/// let mut pose = gps.pose_action();
+	/// action! {
+	/// 	state = pose => odometry.fuse(state?),
+	/// }
+	/// ```
+	pub fn pose_action(&self) -> impl Action<Output = Result<State, DeviceError>> + '_ {
+		struct PoseAction<'a> {
+			gps: &'a GPS,
+			ready: Instant,
+		}
+
+		impl<'a> Action for PoseAction<'a> {
+			type Output = Result<State, DeviceError>;
+
+			fn poll(&mut self) -> Poll<Self::Output> {
+				if self.ready.checked_sub_instant(Instant::now()).is_some() {
+					Poll::Waiting
+				} else {
+					Poll::Complete(self.gps.get_state())
+				}
+			}
+
+			fn next(&mut self) -> NextSleep {
+				NextSleep::Deadline(self.ready)
+			}
+		}
+
+		PoseAction {
+			gps: self,
+			ready: Instant::now() + self.data_rate,
+		}
+	}
+
 	/// Get the heading of the GPS sensor. This will return a value within
 	/// [0, 360) degrees.
 	pub fn get_heading(&self) -> Result<f64, DeviceError> {
@@ -154,6 +244,19 @@ impl GPS {
 		)
 	}
 
+	/// Get the heading of the GPS sensor before any corrections are applied to
+	/// it. This will return a value within [0, 360) degrees.
+	///
+	/// Comparing this against [`GPS::get_heading()`] is useful for working out
+	/// whether a bad heading is coming from the sensor or its corrections.
+	pub fn get_heading_raw(&self) -> Result<f64, DeviceError> {
+		pros_unsafe_err_f!(
+			gps_get_heading_raw,
+			err = DeviceError::errno_imu(),
+			self.get_port()
+		)
+	}
+
 	/// Get the elapsed rotation of the GPS sensor in degrees. This will count
 	/// up above 360 degrees or below 0 degrees.
 	pub fn get_rotation(&self) -> Result<f64, DeviceError> {
@@ -1,10 +1,22 @@
 use crate::bindings::*;
 use crate::devices::DeviceError;
+use crate::math::Angle;
 use crate::ports::Port;
+use crate::rtos::{
+	action::{Action, NextSleep, Poll},
+	time::Instant,
+};
 use crate::util::{PROS_ERR_F, PROS_ERR_U32};
 
+use core::time::Duration;
 use mint::{Quaternion, Vector3};
 
+/// How often to check the status of the IMU while waiting for it to calibrate.
+const CALIBRATE_POLL_PERIOD: Duration = Duration::from_millis(10);
+/// How long to wait for the IMU to report that it has started calibrating
+/// before assuming that it has already finished.
+const CALIBRATE_START_GRACE: Duration = Duration::from_millis(500);
+
 /// A struct which holds and presents a connected Inertial measurement unit
 /// connected to the V5 Brain.
 #[derive(Debug)]
@@ -47,6 +59,99 @@ impl IMU {
 		Ok(())
 	}
 
+	/// Start calibrating the IMU and block the current task until it has
+	/// finished, or until `timeout` has elapsed. Calibration usually takes
+	/// about 2 seconds.
+	///
+	/// # Errors
+	/// Will return [`DeviceError::StillCalibrating`] if the IMU has not
+	/// finished calibrating before the timeout, as well as any error from
+	/// starting calibration or reading the status of the IMU.
+	///
+	/// # Examples
+	/// ```
+	/// imu.calibrate_blocking(Duration::from_secs(3))?;
+	/// let heading = imu.get_heading()?;
+	/// ```
+	pub fn calibrate_blocking(&mut self, timeout: Duration) -> Result<(), DeviceError> {
+		let mut action = self.calibrate_action(timeout)?;
+		loop {
+			match action.poll() {
+				Poll::Complete(res) => return res,
+				Poll::Waiting => action.next().sleep(),
+			}
+		}
+	}
+
+	/// Start calibrating the IMU and return an [`Action`] which completes once
+	/// it has finished, allowing calibration to happen concurrently with other
+	/// initialisation inside of an `action!`. Refer to
+	/// [`IMU::calibrate_blocking()`] for how the timeout and errors are
+	/// handled.
+	///
+	/// After starting calibration the IMU takes a moment before it reports
+	/// that it is calibrating, so the action first waits for the calibrating
+	/// status to appear and only then waits for it to clear. If it does not
+	/// appear within a short grace period the calibration is assumed to have
+	/// already finished.
+	///
+	/// # Examples
+	/// ```
+	/// let mut imu_ready = imu.calibrate_action(Duration::from_secs(3))?;
+	/// action! {
+	/// 	res = imu_ready => res?,
+	/// }
+	/// ```
+	pub fn calibrate_action(
+		&mut self,
+		timeout: Duration,
+	) -> Result<impl Action<Output = Result<(), DeviceError>> + '_, DeviceError> {
+		struct CalibrateAction<'a> {
+			imu: &'a IMU,
+			start: Instant,
+			timeout: Duration,
+			started: bool,
+		}
+
+		impl<'a> Action for CalibrateAction<'a> {
+			type Output = Result<(), DeviceError>;
+
+			fn poll(&mut self) -> Poll<Self::Output> {
+				let calibrating = match self.imu.is_calibrating() {
+					Ok(c) => c,
+					// The IMU may refuse to report anything while it is busy
+					Err(DeviceError::StillCalibrating) => true,
+					Err(e) => return Poll::Complete(Err(e)),
+				};
+				let elapsed = self.start.elapsed();
+
+				if calibrating {
+					self.started = true;
+				} else if self.started || elapsed >= CALIBRATE_START_GRACE {
+					return Poll::Complete(Ok(()));
+				}
+
+				if elapsed >= self.timeout {
+					Poll::Complete(Err(DeviceError::StillCalibrating))
+				} else {
+					Poll::Waiting
+				}
+			}
+
+			fn next(&mut self) -> NextSleep {
+				NextSleep::Timestamp(CALIBRATE_POLL_PERIOD)
+			}
+		}
+
+		self.calibrate()?;
+		Ok(CalibrateAction {
+			imu: self,
+			start: Instant::now(),
+			timeout,
+			started: false,
+		})
+	}
+
 	/// Get a processed value for the rotation of the IMU sensor as a
 	/// quaternion.
 	pub fn get_quaternion(&self) -> Result<Quaternion<f64>, DeviceError> {
@@ -59,6 +164,21 @@ impl IMU {
 		}
 	}
 
+	/// Get the heading of the IMU sensor derived from its orientation
+	/// quaternion, within the range of `[0, 360)` degrees.
+	///
+	/// Unlike the heading accumulated separately by PROS, this is always
+	/// consistent with [`IMU::get_quaternion()`]. The yaw is extracted from
+	/// the quaternion about the sensor's Z axis, which is positive
+	/// counter-clockwise. This is then negated so that, like the PROS heading,
+	/// the returned heading increases as the sensor turns clockwise when
+	/// viewed from above.
+	pub fn get_heading_from_quaternion(&self) -> Result<Angle, DeviceError> {
+		let quat: crate::math::Quaternion = self.get_quaternion()?.into();
+		let (_, _, yaw) = quat.to_euler();
+		Ok(Angle::from_radians(-yaw).normalised())
+	}
+
 	/// Get a processed value for the rotation of the IMU sensor as
 	/// total rotations around the Z axis in degrees
 	pub fn get_rotation(&self) -> Result<f64, DeviceError> {
@@ -70,6 +190,54 @@ impl IMU {
 		}
 	}
 
+	/// Get the heading of the IMU sensor in degrees, a value within the range
+	/// of `[0, 360)`. The heading increases as the sensor turns clockwise.
+	pub fn get_heading(&self) -> Result<f64, DeviceError> {
+		pros_unsafe_err_f!(
+			imu_get_heading,
+			err = DeviceError::errno_imu(),
+			self.get_port()
+		)
+	}
+
+	/// Get the pitch of the IMU sensor in degrees, a value within the range of
+	/// `[-180, 180]`.
+	pub fn get_pitch(&self) -> Result<f64, DeviceError> {
+		pros_unsafe_err_f!(
+			imu_get_pitch,
+			err = DeviceError::errno_imu(),
+			self.get_port()
+		)
+	}
+
+	/// Get the roll of the IMU sensor in degrees, a value within the range of
+	/// `[-180, 180]`.
+	pub fn get_roll(&self) -> Result<f64, DeviceError> {
+		pros_unsafe_err_f!(
+			imu_get_roll,
+			err = DeviceError::errno_imu(),
+			self.get_port()
+		)
+	}
+
+	/// Get the yaw of the IMU sensor in degrees, a value within the range of
+	/// `[-180, 180]`.
+	pub fn get_yaw(&self) -> Result<f64, DeviceError> {
+		pros_unsafe_err_f!(imu_get_yaw, err = DeviceError::errno_imu(), self.get_port())
+	}
+
+	/// Get the pitch, roll and yaw of the IMU sensor in degrees, in that
+	/// order. This reads all three axes at once rather than calling each of
+	/// [`IMU::get_pitch()`], [`IMU::get_roll()`] and [`IMU::get_yaw()`].
+	pub fn get_euler(&self) -> Result<(f64, f64, f64), DeviceError> {
+		let res = unsafe { imu_get_euler(self.get_port()) };
+		if res.pitch == PROS_ERR_F && res.roll == PROS_ERR_F && res.yaw == PROS_ERR_F {
+			Err(DeviceError::errno_imu())
+		} else {
+			Ok((res.pitch, res.roll, res.yaw))
+		}
+	}
+
 	/// Read the raw values from the gryoscope. This is the rate at which it is
 	/// turning.
 	pub fn get_gyro_rate(&self) -> Result<Vector3<f64>, DeviceError> {
@@ -98,6 +266,123 @@ impl IMU {
 		Ok(())
 	}
 
+	/// Set the heading of the IMU sensor in degrees. The value will be wrapped
+	/// into the range of `[0, 360)`.
+	pub fn set_heading(&mut self, heading: f64) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			imu_set_heading,
+			err = DeviceError::errno_imu(),
+			self.get_port(),
+			heading
+		)?;
+		Ok(())
+	}
+
+	/// Set the total rotation of the IMU sensor in degrees, this is the value
+	/// returned by [`IMU::get_rotation()`].
+	pub fn set_rotation(&mut self, rotation: f64) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			imu_set_rotation,
+			err = DeviceError::errno_imu(),
+			self.get_port(),
+			rotation
+		)?;
+		Ok(())
+	}
+
+	/// Set the pitch of the IMU sensor in degrees, clamped to `[-180, 180]`.
+	pub fn set_pitch(&mut self, pitch: f64) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			imu_set_pitch,
+			err = DeviceError::errno_imu(),
+			self.get_port(),
+			pitch
+		)?;
+		Ok(())
+	}
+
+	/// Set the roll of the IMU sensor in degrees, clamped to `[-180, 180]`.
+	pub fn set_roll(&mut self, roll: f64) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			imu_set_roll,
+			err = DeviceError::errno_imu(),
+			self.get_port(),
+			roll
+		)?;
+		Ok(())
+	}
+
+	/// Set the yaw of the IMU sensor in degrees, clamped to `[-180, 180]`.
+	pub fn set_yaw(&mut self, yaw: f64) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			imu_set_yaw,
+			err = DeviceError::errno_imu(),
+			self.get_port(),
+			yaw
+		)?;
+		Ok(())
+	}
+
+	/// Reset the heading of the IMU sensor to 0.
+	pub fn tare_heading(&mut self) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			imu_tare_heading,
+			err = DeviceError::errno_imu(),
+			self.get_port()
+		)?;
+		Ok(())
+	}
+
+	/// Reset the rotation of the IMU sensor to 0.
+	pub fn tare_rotation(&mut self) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			imu_tare_rotation,
+			err = DeviceError::errno_imu(),
+			self.get_port()
+		)?;
+		Ok(())
+	}
+
+	/// Reset the pitch of the IMU sensor to 0.
+	pub fn tare_pitch(&mut self) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			imu_tare_pitch,
+			err = DeviceError::errno_imu(),
+			self.get_port()
+		)?;
+		Ok(())
+	}
+
+	/// Reset the roll of the IMU sensor to 0.
+	pub fn tare_roll(&mut self) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			imu_tare_roll,
+			err = DeviceError::errno_imu(),
+			self.get_port()
+		)?;
+		Ok(())
+	}
+
+	/// Reset the yaw of the IMU sensor to 0.
+	pub fn tare_yaw(&mut self) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			imu_tare_yaw,
+			err = DeviceError::errno_imu(),
+			self.get_port()
+		)?;
+		Ok(())
+	}
+
+	/// Reset the pitch, roll and yaw of the IMU sensor to 0.
+	pub fn tare_euler(&mut self) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			imu_tare_euler,
+			err = DeviceError::errno_imu(),
+			self.get_port()
+		)?;
+		Ok(())
+	}
+
 	/// Check to see if the IMU sensor is currently calibrating.
 	pub fn is_calibrating(&self) -> Result<bool, DeviceError> {
 		match unsafe { imu_get_status(self.get_port()) } {
@@ -0,0 +1,268 @@
+//! The legacy LCD emulator which is drawn on the V5 Brain's screen.
+//!
+//! The emulator shows eight lines of text along with three buttons, making it
+//! a quick way to build simple menus such as an autonomous selector. It should
+//! not be used at the same time as the other functions in
+//! [`screen`][crate::devices::screen] as they will draw over each other.
+
+use bitflags::bitflags;
+
+use crate::bindings::*;
+use crate::devices::{usd::File, DeviceError};
+use crate::rtos::action::{Action, NextSleep, Poll};
+use crate::rtos::tasks::Task;
+use crate::util::to_cstring;
+
+use alloc::{format, string::String, vec::Vec};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::time::Duration;
+
+/// The amount of lines of text the LCD emulator has.
+pub const LINES: i16 = 8;
+
+/// How often to check the buttons when waiting for a press.
+const POLL_PERIOD: Duration = Duration::from_millis(20);
+
+bitflags! {
+	/// The buttons of the LCD emulator which are currently pressed.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub struct LcdButtons: u8 {
+		const NONE = 0x0;
+		const RIGHT = 0x1;
+		const CENTER = 0x2;
+		const LEFT = 0x4;
+	}
+}
+
+/// A handle to the LCD emulator, which is only available once it has been
+/// initialised.
+#[derive(Debug)]
+pub struct Lcd {
+	_private: (),
+}
+
+impl Lcd {
+	/// Initialise the LCD emulator and draw it to the screen. If the emulator
+	/// has already been initialised a handle to it is returned as is.
+	pub fn initialize() -> Result<Lcd, DeviceError> {
+		if unsafe { lcd_is_initialized() } || unsafe { lcd_initialize() } {
+			Ok(Lcd { _private: () })
+		} else {
+			Err(DeviceError::errno_lcd())
+		}
+	}
+
+	/// Set the text of a line of the LCD emulator, replacing anything which was
+	/// previously on that line.
+	///
+	/// # Debug Assertions
+	/// Asserts that the line is within the [`LINES`] of the emulator.
+	pub fn set_text(&mut self, line: i16, text: &str) -> Result<(), DeviceError> {
+		debug_assert!((0..LINES).contains(&line));
+		let text = to_cstring(String::from(text));
+		if unsafe { lcd_set_text(line, text.as_ptr()) } {
+			Ok(())
+		} else {
+			Err(DeviceError::errno_lcd())
+		}
+	}
+
+	/// Clear all of the lines of the LCD emulator.
+	pub fn clear(&mut self) -> Result<(), DeviceError> {
+		if unsafe { lcd_clear() } {
+			Ok(())
+		} else {
+			Err(DeviceError::errno_lcd())
+		}
+	}
+
+	/// Get which of the buttons on the LCD emulator are currently pressed.
+	pub fn read_buttons(&self) -> LcdButtons {
+		LcdButtons::from_bits_truncate(unsafe { lcd_read_buttons() })
+	}
+
+	/// Returns an action which will complete once any of `buttons` becomes
+	/// pressed, containing the buttons which were newly pressed. Buttons which
+	/// are already held down when this is called must be released and pressed
+	/// again to complete the action.
+	///
+	/// # Examples
+	/// ```
+	/// let mut pressed = lcd.pressed(LcdButtons::LEFT | LcdButtons::RIGHT);
+	/// action! {
+	/// 	b = pressed => println!("pressed {:?}", b),
+	/// }
+	/// ```
+	pub fn pressed(&self, buttons: LcdButtons) -> impl Action<Output = LcdButtons> + '_ {
+		struct PressedAction<'a> {
+			lcd: &'a Lcd,
+			buttons: LcdButtons,
+			last: LcdButtons,
+		}
+
+		impl<'a> Action for PressedAction<'a> {
+			type Output = LcdButtons;
+
+			fn poll(&mut self) -> Poll<Self::Output> {
+				let current = self.lcd.read_buttons() & self.buttons;
+				let new = current & !self.last;
+				self.last = current;
+				if new.is_empty() {
+					Poll::Waiting
+				} else {
+					Poll::Complete(new)
+				}
+			}
+
+			fn next(&mut self) -> NextSleep {
+				NextSleep::Timestamp(POLL_PERIOD)
+			}
+		}
+
+		PressedAction {
+			lcd: self,
+			buttons,
+			last: self.read_buttons() & buttons,
+		}
+	}
+}
+
+/// A menu on the LCD emulator for picking which autonomous routine to run.
+///
+/// Each entry is a name along with a routine of any type `T`, usually a
+/// function. The left and right buttons cycle through the entries and the
+/// centre button confirms the choice. The selection is stored atomically so
+/// it can be read from [`Robot::autonomous()`][crate::Robot::autonomous()]
+/// while [`Robot::competition_init()`][crate::Robot::competition_init()] is
+/// still running the menu.
+///
+/// # Examples
+/// ```
+/// // in Robot::new()
+/// let selector = AutonSelector::new(vec![
+/// 	("left side", left_auton as fn(&Bot)),
+/// 	("right side", right_auton),
+/// 	("skills", skills_auton),
+/// ])
+/// .with_save_file("auton.txt");
+///
+/// // in Robot::competition_init()
+/// let mut lcd = Lcd::initialize()?;
+/// self.selector.run(&mut lcd)?;
+///
+/// // in Robot::autonomous()
+/// (self.selector.routine())(self);
+/// ```
+pub struct AutonSelector<T> {
+	entries: Vec<(&'static str, T)>,
+	selected: AtomicUsize,
+	save_file: Option<&'static str>,
+}
+
+impl<T> AutonSelector<T> {
+	/// Create a new selector from a list of named routines, starting with the
+	/// first one selected.
+	///
+	/// # Panics
+	/// Panics if `entries` is empty.
+	pub fn new(entries: Vec<(&'static str, T)>) -> Self {
+		assert!(
+			!entries.is_empty(),
+			"an AutonSelector needs at least one entry"
+		);
+		AutonSelector {
+			entries,
+			selected: AtomicUsize::new(0),
+			save_file: None,
+		}
+	}
+
+	/// Remember the confirmed selection in a file on the SD card, so it is
+	/// kept when the program is restarted. If the file already holds a
+	/// selection it is loaded straight away.
+	///
+	/// Problems reading or writing the file, such as there being no SD card
+	/// inserted, are ignored and the selector just works without it.
+	pub fn with_save_file(mut self, path: &'static str) -> Self {
+		let mut buf = Vec::new();
+		let saved = File::open(path)
+			.and_then(|mut f| f.read_to_end(&mut buf))
+			.ok()
+			.and_then(|_| {
+				core::str::from_utf8(&buf)
+					.ok()?
+					.trim()
+					.parse::<usize>()
+					.ok()
+			});
+		if let Some(i) = saved.filter(|&i| i < self.entries.len()) {
+			self.selected.store(i, Ordering::Relaxed);
+		}
+		self.save_file = Some(path);
+		self
+	}
+
+	/// Get the index of the currently selected entry.
+	pub fn selected(&self) -> usize {
+		self.selected.load(Ordering::Relaxed)
+	}
+
+	/// Get the name of the currently selected entry.
+	pub fn selected_name(&self) -> &'static str {
+		self.entries[self.selected()].0
+	}
+
+	/// Get the routine of the currently selected entry.
+	pub fn routine(&self) -> &T {
+		&self.entries[self.selected()].1
+	}
+
+	/// Draw the menu to the LCD emulator, replacing anything on it.
+	pub fn draw(&self, lcd: &mut Lcd) -> Result<(), DeviceError> {
+		let i = self.selected();
+		lcd.clear()?;
+		lcd.set_text(0, "Autonomous")?;
+		lcd.set_text(2, &format!("< {} >", self.entries[i].0))?;
+		lcd.set_text(3, &format!("{}/{}", i + 1, self.entries.len()))?;
+		lcd.set_text(LINES - 1, "LEFT/RIGHT change, CENTER ok")
+	}
+
+	/// Show the menu and block until the centre button is pressed, returning
+	/// the index of the confirmed entry. The selection can be read with
+	/// [`AutonSelector::selected()`] while this is still running.
+	pub fn run(&self, lcd: &mut Lcd) -> Result<usize, DeviceError> {
+		let len = self.entries.len();
+		let mut last = lcd.read_buttons();
+		loop {
+			self.draw(lcd)?;
+
+			// Wait for a button to be newly pressed
+			let pressed = loop {
+				Task::delay(POLL_PERIOD);
+				let current = lcd.read_buttons();
+				let new = current & !last;
+				last = current;
+				if !new.is_empty() {
+					break new;
+				}
+			};
+
+			let i = self.selected();
+			if pressed.contains(LcdButtons::CENTER) {
+				self.save(i);
+				lcd.set_text(LINES - 1, "Selected")?;
+				return Ok(i);
+			} else if pressed.contains(LcdButtons::LEFT) {
+				self.selected.store((i + len - 1) % len, Ordering::Relaxed);
+			} else if pressed.contains(LcdButtons::RIGHT) {
+				self.selected.store((i + 1) % len, Ordering::Relaxed);
+			}
+		}
+	}
+
+	fn save(&self, i: usize) {
+		if let Some(path) = self.save_file {
+			let _ = File::create(path).and_then(|mut f| f.write_all(format!("{}\n", i).as_bytes()));
+		}
+	}
+}
@@ -7,57 +7,116 @@ pub struct LedStrip {
 	_triport: TriPort,
 	led: ext_adi_led_t,
 	colours: [u32; Self::MAX_LED],
+	len: usize,
 }
 
 impl LedStrip {
 	/// The maximum amount of LEDs addressable on a single port.
 	pub const MAX_LED: usize = 64;
 
+	/// Create a new LED strip of [`LedStrip::MAX_LED`] LEDs.
 	pub unsafe fn new(triport: TriPort) -> Result<Self, DeviceError> {
+		Self::with_length(triport, Self::MAX_LED)
+	}
+
+	/// Create a new LED strip with `len` LEDs. Only this many LEDs are sent to
+	/// the strip on each update, so this should match how many are actually
+	/// connected.
+	///
+	/// # Errors
+	/// Returns [`DeviceError::IndexRange`] if `len` is 0 or more than
+	/// [`LedStrip::MAX_LED`].
+	pub unsafe fn with_length(triport: TriPort, len: usize) -> Result<Self, DeviceError> {
+		if !(1..=Self::MAX_LED).contains(&len) {
+			return Err(DeviceError::IndexRange);
+		}
 		let led = pros_unsafe_err!(
 			ext_adi_led_init,
 			err = DeviceError::errno_adi(),
 			triport.ext_port.get(),
 			triport.port.get()
 		)?;
-		let colours = [Colour::WHITE.as_u32(); Self::MAX_LED];
+		let colours = [0; Self::MAX_LED];
 		Ok(LedStrip {
 			_triport: triport,
 			led,
 			colours,
+			len,
 		})
 	}
 
+	/// Get the amount of LEDs in this strip.
+	#[inline]
+	pub fn get_length(&self) -> usize {
+		self.len
+	}
+
 	pub fn set_all(&mut self, colour: Colour) -> Result<(), DeviceError> {
 		pros_unsafe_err!(
 			ext_adi_led_set_all,
 			err = DeviceError::errno_adi(),
 			self.led,
 			self.colours.as_mut_ptr(),
-			self.colours.len() as u32,
+			self.len as u32,
 			colour.as_u32()
 		)?;
 		Ok(())
 	}
 
+	/// Turn off all of the LEDs in the strip.
 	pub fn clear_all(&mut self) -> Result<(), DeviceError> {
-		self.set_all(Colour::WHITE)
+		self.set_all(Colour::BLACK)
+	}
+
+	/// Set the LEDs from `start` up to but not including `end` to `colour`,
+	/// leaving the rest of the strip as it is.
+	///
+	/// # Errors
+	/// Returns [`DeviceError::IndexRange`] if the range is not within the
+	/// length of the strip.
+	pub fn set_range(
+		&mut self,
+		start: usize,
+		end: usize,
+		colour: Colour,
+	) -> Result<(), DeviceError> {
+		if start > end || end > self.len {
+			return Err(DeviceError::IndexRange);
+		}
+		self.colours[start..end].fill(colour.as_u32());
+		pros_unsafe_err!(
+			ext_adi_led_set,
+			err = DeviceError::errno_adi(),
+			self.led,
+			self.colours.as_mut_ptr(),
+			self.len as u32
+		)?;
+		Ok(())
 	}
 
+	/// Set a single LED to `colour`.
+	///
+	/// # Errors
+	/// Returns [`DeviceError::IndexRange`] if `index` is not within the length
+	/// of the strip.
 	pub fn set_pixel(&mut self, colour: Colour, index: u32) -> Result<(), DeviceError> {
+		if index as usize >= self.len {
+			return Err(DeviceError::IndexRange);
+		}
 		pros_unsafe_err!(
 			ext_adi_led_set_pixel,
 			err = DeviceError::errno_adi(),
 			self.led,
 			self.colours.as_mut_ptr(),
-			self.colours.len() as u32,
+			self.len as u32,
 			colour.as_u32(),
 			index
 		)?;
 		Ok(())
 	}
 
+	/// Turn off a single LED.
 	pub fn clear_pixel(&mut self, index: u32) -> Result<(), DeviceError> {
-		self.set_pixel(Colour::WHITE, index)
+		self.set_pixel(Colour::BLACK, index)
 	}
 }
@@ -5,20 +5,29 @@
 //! which works for internal TriPorts, and into the [`expander`] module for to
 //! be able to create more TriPorts.
 
+pub mod adi;
 pub mod controller;
 pub mod distance;
 pub mod expander;
 pub mod gps;
 pub mod imu;
+pub mod lcd;
 pub mod led;
 pub mod motor;
+pub mod optical;
 pub mod rotation;
+pub mod screen;
+pub mod serial;
+pub mod usd;
 pub mod vision;
 
 use smallvec::SmallVec;
 
+use crate::math::FloatMath;
 use crate::util::get_errno;
 
+use core::fmt;
+
 /// Possible errors that could be returned from devices in their operation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DeviceError {
@@ -39,6 +48,8 @@ pub enum DeviceError {
 	PortNotRotationSensor,
 	/// The Port chosen cannot be configured as a vision sensor,
 	PortNotVisionSensor,
+	/// The Port chosen cannot be configured as an optical sensor,
+	PortNotOptical,
 	/// The Vision sensor failed for an unknown reason,
 	VisionUnknown,
 	/// The Vision sensor cannot see any other objects which meet the
@@ -46,6 +57,14 @@ pub enum DeviceError {
 	VisionObjectsDeficit,
 	/// The port chosen cannot be configured as an ADI port,
 	PortNotADI,
+	/// The LCD emulator has not been initialised,
+	LcdNotInitialized,
+	/// An index was outside of the range supported by the device,
+	IndexRange,
+	/// The device plugged into the port is not the type that was expected,
+	WrongDeviceType,
+	/// The controller is not connected to the V5 Brain,
+	ControllerNotConnected,
 	/// The V5 Brain ran out of memory
 	OutOfMemory,
 	/// An unknown error,
@@ -137,6 +156,44 @@ impl DeviceError {
 		}
 	}
 
+	pub(crate) fn errno_optical() -> Self {
+		match get_errno() {
+			libc::ENODEV => Self::PortNotOptical,
+			libc::ENXIO => Self::PortRange,
+			e => {
+				if cfg!(debug_assertions) {
+					panic!("reached unknown error ({e})");
+				}
+				Self::Unknown
+			}
+		}
+	}
+
+	pub(crate) fn errno_lcd() -> Self {
+		match get_errno() {
+			libc::ENXIO => Self::LcdNotInitialized,
+			e => {
+				if cfg!(debug_assertions) {
+					panic!("reached unknown error ({e})");
+				}
+				Self::Unknown
+			}
+		}
+	}
+
+	pub(crate) fn errno_registry() -> Self {
+		match get_errno() {
+			libc::ENXIO => Self::PortRange,
+			libc::EADDRINUSE => Self::ResourceInUse,
+			e => {
+				if cfg!(debug_assertions) {
+					panic!("reached unknown error ({e})");
+				}
+				Self::Unknown
+			}
+		}
+	}
+
 	pub(crate) fn errno_adi() -> Self {
 		match get_errno() {
 			libc::ENXIO => Self::PortRange,
@@ -151,6 +208,33 @@ impl DeviceError {
 	}
 }
 
+impl fmt::Display for DeviceError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::ResourceInUse => "resource is in use by something else",
+			Self::PortRange => "port is out of range",
+			Self::PortNotDistance => "port not a distance sensor",
+			Self::PortNotMotor => "port not a motor",
+			Self::PortNotIMU => "port not an IMU",
+			Self::StillCalibrating => "sensor is still calibrating",
+			Self::PortNotRotationSensor => "port not a rotation sensor",
+			Self::PortNotVisionSensor => "port not a vision sensor",
+			Self::PortNotOptical => "port not an optical sensor",
+			Self::VisionUnknown => "vision sensor failed for an unknown reason",
+			Self::VisionObjectsDeficit => "vision sensor cannot see enough objects",
+			Self::PortNotADI => "port not an ADI port",
+			Self::LcdNotInitialized => "LCD emulator has not been initialised",
+			Self::IndexRange => "index is out of range",
+			Self::WrongDeviceType => "wrong type of device plugged into port",
+			Self::ControllerNotConnected => "controller is not connected",
+			Self::OutOfMemory => "out of memory",
+			Self::Unknown => "unknown error",
+		})
+	}
+}
+
+impl core::error::Error for DeviceError {}
+
 #[derive(Debug, Copy, Clone)]
 #[repr(transparent)]
 pub struct Colour(u32);
@@ -160,6 +244,7 @@ impl Colour {
 	pub const RED: Self = Self::new(0xFF, 0x00, 0x00);
 	pub const GREEN: Self = Self::new(0x00, 0xFF, 0x00);
 	pub const BLUE: Self = Self::new(0x00, 0x00, 0xFF);
+	pub const BLACK: Self = Self::new(0x00, 0x00, 0x00);
 
 	const R_MASK: u32 = 0x00_FF_00_00;
 	const G_MASK: u32 = 0x00_00_FF_00;
@@ -178,19 +263,67 @@ impl Colour {
 		self.0
 	}
 
+	/// Create a new colour from its hue in degrees, and its saturation and
+	/// value within `[0, 1]`. The hue wraps around every 360 degrees while the
+	/// saturation and value are clamped.
+	///
+	/// # Examples
+	/// ```
+	/// // a rainbow along the strip
+	/// for i in 0..LedStrip::MAX_LED {
+	/// 	let hue = i as f32 * 360.0 / LedStrip::MAX_LED as f32;
+	/// 	strip.set_pixel(Colour::from_hsv(hue, 1.0, 1.0), i as u32)?;
+	/// }
+	/// ```
+	pub fn from_hsv(h: f32, s: f32, v: f32) -> Self {
+		let mut h = h % 360.0;
+		if h < 0.0 {
+			h += 360.0;
+		}
+		let s = s.clamp(0.0, 1.0);
+		let v = v.clamp(0.0, 1.0);
+
+		let c = v * s;
+		let sector = h / 60.0;
+		let x = c * (1.0 - (sector % 2.0 - 1.0).abs());
+		let (r, g, b) = match sector as u32 {
+			0 => (c, x, 0.0),
+			1 => (x, c, 0.0),
+			2 => (0.0, c, x),
+			3 => (0.0, x, c),
+			4 => (x, 0.0, c),
+			_ => (c, 0.0, x),
+		};
+		let m = v - c;
+		let channel = |f: f32| ((f + m) * 255.0 + 0.5) as u8;
+		Self::new(channel(r), channel(g), channel(b))
+	}
+
+	/// Blend between two colours, where a `t` of 0 gives `self` and a `t` of 1
+	/// gives `other`. `t` is clamped to `[0, 1]`.
+	pub fn lerp(self, other: Colour, t: f32) -> Self {
+		let t = t.clamp(0.0, 1.0);
+		let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t + 0.5) as u8;
+		Self::new(
+			channel(self.get_r(), other.get_r()),
+			channel(self.get_g(), other.get_g()),
+			channel(self.get_b(), other.get_b()),
+		)
+	}
+
 	#[inline]
 	pub const fn set_r(self, r: u8) -> Self {
-		Self((self.0 & !Self::R_MASK) | (r as u32) << Self::R_OFFSET)
+		Self((self.0 & !Self::R_MASK) | ((r as u32) << Self::R_OFFSET))
 	}
 
 	#[inline]
 	pub const fn set_g(self, g: u8) -> Self {
-		Self((self.0 & !Self::G_MASK) | (g as u32) << Self::G_OFFSET)
+		Self((self.0 & !Self::G_MASK) | ((g as u32) << Self::G_OFFSET))
 	}
 
 	#[inline]
 	pub const fn set_b(self, b: u8) -> Self {
-		Self((self.0 & !Self::B_MASK) | (b as u32) << Self::B_OFFSET)
+		Self((self.0 & !Self::B_MASK) | ((b as u32) << Self::B_OFFSET))
 	}
 
 	#[inline]
@@ -219,7 +352,8 @@ pub enum Direction {
 	Reverse,
 }
 
-use crate::ports::{Port, TriPort};
+use crate::bindings::registry_get_plugged_type;
+use crate::ports::{DeviceType, Port, TriPort};
 use controller::Controller;
 
 /// A structure which represents all the possible devices connected to the V5
@@ -280,12 +414,84 @@ impl Devices {
 
 	/// Takes the slave controller out of this [`Devices`] structure.
 	///
+	/// This succeeds whether or not a partner controller is actually connected,
+	/// use [`Controller::exists()`] to check for one.
+	///
 	/// # Panics
 	/// This function will panic if the slave controller has already been taken.
 	pub fn take_slave_controller(&mut self) -> Controller {
 		self.slave_controller.take().unwrap()
 	}
 
+	/// Borrow the master controller without taking it out of this [`Devices`]
+	/// structure, returns `None` if it has already been taken.
+	pub fn master(&self) -> Option<&Controller> {
+		self.master_controller.as_ref()
+	}
+
+	/// Borrow the slave controller without taking it out of this [`Devices`]
+	/// structure, returns `None` if it has already been taken.
+	pub fn slave(&self) -> Option<&Controller> {
+		self.slave_controller.as_ref()
+	}
+
+	/// Borrow a Port without taking it out of this [`Devices`] structure. The
+	/// index passed to this function is the same as that of the port.
+	///
+	/// This is useful for inspecting a port during setup, constructing a device
+	/// still requires taking ownership with [`Devices::take_port()`].
+	///
+	/// # Errors
+	/// May return a [`DeviceError::PortRange`] if the port index is out of
+	/// range or a [`DeviceError::ResourceInUse`] if the port has already been
+	/// taken.
+	pub fn port(&self, index: usize) -> Result<&Port, DeviceError> {
+		if (1..=21).contains(&index) {
+			self.ports[index - 1]
+				.as_ref()
+				.ok_or(DeviceError::ResourceInUse)
+		} else {
+			Err(DeviceError::PortRange)
+		}
+	}
+
+	/// Borrow a TriPort without taking it out of this [`Devices`] structure.
+	/// The index passed to this function is the same as that of the port.
+	///
+	/// # Errors
+	/// May return a [`DeviceError::PortRange`] if the port index is out of
+	/// range or a [`DeviceError::ResourceInUse`] if the port has already been
+	/// taken.
+	pub fn triport(&self, index: usize) -> Result<&TriPort, DeviceError> {
+		if (1..=8).contains(&index) {
+			self.triports[index - 1]
+				.as_ref()
+				.ok_or(DeviceError::ResourceInUse)
+		} else {
+			Err(DeviceError::PortRange)
+		}
+	}
+
+	/// Get what is currently plugged into every port of the V5 Brain, paired
+	/// with the index of that port. This includes ports which have already been
+	/// taken, and ports with nothing plugged in are [`DeviceType::None`].
+	///
+	/// This is the same as calling [`Port::plugged_type()`] for every port,
+	/// and is useful for checking the wiring of a robot when it starts.
+	///
+	/// # Examples
+	/// ```
+	/// for (port, ty) in devices.scan() {
+	/// 	println!("port {}: {:?}", port, ty);
+	/// }
+	/// ```
+	pub fn scan(&self) -> [(u8, DeviceType); 21] {
+		core::array::from_fn(|i| {
+			let ty = unsafe { registry_get_plugged_type(i as u8) };
+			(i as u8 + 1, ty.into())
+		})
+	}
+
 	/// Take a Port out of this [`Devices`] structure. The index passed to this
 	/// function is the same as that of the port.
 	///
@@ -303,18 +509,29 @@ impl Devices {
 	/// assert_eq!(1, port.get());
 	/// ```
 	pub fn take_port(&mut self, index: usize) -> Result<Port, DeviceError> {
-		let within = (1..=21).contains(&index);
 		debug_assert!(
-			within,
+			(1..=21).contains(&index),
 			"This port value is not within the range of 1..=21 ({})",
 			index
 		);
 
-		if within {
-			match self.ports[index - 1].take() {
-				Some(p) => Ok(p),
-				None => Err(DeviceError::ResourceInUse),
-			}
+		self.try_take_port(index)
+	}
+
+	/// Take a Port out of this [`Devices`] structure, the same as
+	/// [`Devices::take_port()`] except an out of range index is only ever
+	/// reported as an error. Use this when the index is not known until
+	/// runtime, such as when it is read from a configuration file.
+	///
+	/// # Errors
+	/// May return a [`DeviceError::PortRange`] if the port index is out of
+	/// range or a [`DeviceError::ResourceInUse`] if the port has already been
+	/// taken.
+	pub fn try_take_port(&mut self, index: usize) -> Result<Port, DeviceError> {
+		if (1..=21).contains(&index) {
+			self.ports[index - 1]
+				.take()
+				.ok_or(DeviceError::ResourceInUse)
 		} else {
 			Err(DeviceError::PortRange)
 		}
@@ -331,18 +548,28 @@ impl Devices {
 	/// # Debug Assertions
 	/// Assertions that the port index with the valid range for the V5 Brain.
 	pub fn take_triport(&mut self, index: usize) -> Result<TriPort, DeviceError> {
-		let within = (1..=8).contains(&index);
 		debug_assert!(
-			within,
+			(1..=8).contains(&index),
 			"This port value is not within the range of 1..=8 ({})",
 			index
 		);
 
-		if within {
-			match self.triports[index - 1].take() {
-				Some(p) => Ok(p),
-				None => Err(DeviceError::ResourceInUse),
-			}
+		self.try_take_triport(index)
+	}
+
+	/// Take a TriPort out of this [`Devices`] structure, the same as
+	/// [`Devices::take_triport()`] except an out of range index is only ever
+	/// reported as an error.
+	///
+	/// # Errors
+	/// May return a [`DeviceError::PortRange`] if the port index is out of
+	/// range or a [`DeviceError::ResourceInUse`] if the port has already been
+	/// taken.
+	pub fn try_take_triport(&mut self, index: usize) -> Result<TriPort, DeviceError> {
+		if (1..=8).contains(&index) {
+			self.triports[index - 1]
+				.take()
+				.ok_or(DeviceError::ResourceInUse)
 		} else {
 			Err(DeviceError::PortRange)
 		}
@@ -3,11 +3,29 @@ use bitflags::bitflags;
 use crate::bindings::*;
 use crate::devices::{DeviceError, Direction};
 use crate::ports::Port;
+use crate::rtos::action::{Action, NextSleep, Poll};
+use crate::rtos::time::Instant;
+use crate::util::{clear_errno, get_errno};
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+
+/// How often the motor updates its readings, there is no point polling a motor
+/// any faster than this.
+const POLL_PERIOD: Duration = Duration::from_millis(10);
+
+/// How long after starting to home a motor the current is ignored for, so the
+/// spike in current from getting the mechanism moving is not mistaken for
+/// hitting the hard stop.
+const HOME_SETTLE_PERIOD: Duration = Duration::from_millis(100);
 
 /// A struct which holds and represent a connected V5 motor
 #[derive(Debug)]
 pub struct Motor {
 	pub port: Port,
+	// The brake mode to go back to on `release()` while `hold_here()` is active
+	released_brake_mode: Option<BrakeMode>,
 }
 
 impl Motor {
@@ -29,7 +47,10 @@ impl Motor {
 		gearset: Gearset,
 		units: EncoderUnits,
 	) -> Result<Self, DeviceError> {
-		let mut m = Motor { port };
+		let mut m = Motor {
+			port,
+			released_brake_mode: None,
+		};
 		m.set_brake_mode(BrakeMode::Coast)?;
 		m.set_reversed(reversed)?;
 		m.set_gearing(gearset)?;
@@ -80,14 +101,67 @@ impl Motor {
 		Ok(())
 	}
 
-	pub fn move_velocity(&mut self, velocity: i32) -> Result<(), DeviceError> {
-		// Debug assertion to make sure that velocity is getting set
-		// correctly
-		match self.get_gearing()? {
-			Gearset::Blue => debug_assert!(velocity >= -600 && velocity <= 600),
-			Gearset::Green => debug_assert!(velocity >= -200 && velocity <= 200),
-			Gearset::Red => debug_assert!(velocity >= -100 && velocity <= 100),
+	/// Move the motor by `offset` relative to its current position, returning
+	/// an [`Action`] which completes once the motor has travelled `offset`
+	/// within `tolerance`. Both are in the currently configured
+	/// [`EncoderUnits`] for this motor.
+	///
+	/// The starting position is read before the move is commanded, so the
+	/// action measures the distance actually travelled by the encoder rather
+	/// than trusting the motor's target. When the motor has been reversed with
+	/// [`Motor::set_reversed()`] PROS reverses both the command and the
+	/// position reading, so `offset` is always in the same direction as the
+	/// positions read from this motor.
+	///
+	/// The action will complete with an error if the position of the motor can
+	/// no longer be read.
+	///
+	/// # Examples
+	/// ```
+	/// let mut arm_moved = arm.move_relative_action(0.5, 100, 0.01)?;
+	/// action! {
+	/// 	res = arm_moved => res?,
+	/// }
+	/// ```
+	pub fn move_relative_action(
+		&mut self,
+		offset: f64,
+		velocity: i32,
+		tolerance: f64,
+	) -> Result<impl Action<Output = Result<(), DeviceError>> + '_, DeviceError> {
+		struct MoveRelativeAction<'a> {
+			motor: &'a Motor,
+			target: f64,
+			tolerance: f64,
+		}
+
+		impl<'a> Action for MoveRelativeAction<'a> {
+			type Output = Result<(), DeviceError>;
+
+			fn poll(&mut self) -> Poll<Self::Output> {
+				match self.motor.at_position(self.target, self.tolerance) {
+					Ok(true) => Poll::Complete(Ok(())),
+					Ok(false) => Poll::Waiting,
+					Err(e) => Poll::Complete(Err(e)),
+				}
+			}
+
+			fn next(&mut self) -> NextSleep {
+				NextSleep::Timestamp(POLL_PERIOD)
+			}
 		}
+
+		let start = self.get_position()?;
+		self.move_relative(offset, velocity)?;
+		Ok(MoveRelativeAction {
+			motor: self,
+			target: start + offset,
+			tolerance,
+		})
+	}
+
+	pub fn move_velocity(&mut self, velocity: i32) -> Result<(), DeviceError> {
+		self.debug_assert_velocity(velocity)?;
 		pros_unsafe_err!(
 			motor_move_velocity,
 			err = DeviceError::errno_motor(),
@@ -97,6 +171,31 @@ impl Motor {
 		Ok(())
 	}
 
+	/// Set the velocity of the motor as a fraction of the maximum velocity of
+	/// its gearset, within `[-1, 1]`, so motors with different gearsets can be
+	/// driven at the same proportional speed. The fraction is clamped.
+	///
+	/// Like [`Motor::move_velocity()`] this is a velocity command which is held
+	/// by the motor's internal PID controller, not a voltage command.
+	pub fn set_velocity_fraction(&mut self, frac: f64) -> Result<(), DeviceError> {
+		let max = self.get_gearing()?.max_velocity();
+		let velocity = (frac.clamp(-1.0, 1.0) * max as f64) as i32;
+		self.move_velocity(velocity)
+	}
+
+	/// Debug assertion to make sure that velocity is within the bounds of the
+	/// motor's current gearset. The gearing is only read in debug builds.
+	fn debug_assert_velocity(&self, velocity: i32) -> Result<(), DeviceError> {
+		if cfg!(debug_assertions) {
+			let max = self.get_gearing()?.max_velocity();
+			debug_assert!(
+				(-max..=max).contains(&velocity),
+				"velocity is not within the range of the gearset -{max}..={max} ({velocity})"
+			);
+		}
+		Ok(())
+	}
+
 	pub fn move_voltage(&mut self, voltage: i16) -> Result<(), DeviceError> {
 		debug_assert!(voltage >= -12000 && voltage <= 12000);
 		pros_unsafe_err!(
@@ -108,17 +207,15 @@ impl Motor {
 		Ok(())
 	}
 
+	/// Change the target velocity of a profiled movement which is currently in
+	/// progress, as started by [`Motor::move_absolute()`] or
+	/// [`Motor::move_relative()`]. The velocity bounds are the same as those of
+	/// [`Motor::move_velocity()`].
 	pub fn modify_velocity(&mut self, velocity: i32) -> Result<(), DeviceError> {
-		// Debug assertion to make sure that velocity is getting set
-		// correctly
-		match self.get_gearing()? {
-			Gearset::Blue => debug_assert!(velocity >= 600 && velocity <= 600),
-			Gearset::Green => debug_assert!(velocity >= 200 && velocity <= 200),
-			Gearset::Red => debug_assert!(velocity >= 100 && velocity <= 100),
-		}
+		self.debug_assert_velocity(velocity)?;
 		debug_assert!(velocity != 0);
 		pros_unsafe_err!(
-			motor_move_voltage,
+			motor_modify_profiled_velocity,
 			err = DeviceError::errno_motor(),
 			self.get_port(),
 			velocity
@@ -142,6 +239,9 @@ impl Motor {
 		)
 	}
 
+	/// Get the velocity of the motor in RPM. If the motor is reversed this is
+	/// already negated by PROS, so commanding a positive velocity or voltage
+	/// always reads back as a positive velocity.
 	pub fn get_actual_velocity(&self) -> Result<f64, DeviceError> {
 		pros_unsafe_err_f!(
 			motor_get_actual_velocity,
@@ -150,9 +250,10 @@ impl Motor {
 		)
 	}
 
+	/// Get the current drawn by the motor in milliamps.
 	pub fn get_current_draw(&self) -> Result<u32, DeviceError> {
 		let i = pros_unsafe_err!(
-			motor_get_target_velocity,
+			motor_get_current_draw,
 			err = DeviceError::errno_motor(),
 			self.get_port()
 		)?;
@@ -181,23 +282,44 @@ impl Motor {
 	}
 
 	pub fn get_faults(&self) -> Result<FaultFlags, DeviceError> {
-		let _f = pros_unsafe_err_u32!(
+		let f = pros_unsafe_err_u32!(
 			motor_get_faults,
 			err = DeviceError::errno_motor(),
 			self.get_port()
 		)?;
-		unimplemented!()
+		Ok(FaultFlags::from_bits_truncate(f))
 	}
 
 	pub fn get_flags(&self) -> Result<MotorFlags, DeviceError> {
-		let _f = pros_unsafe_err_u32!(
+		let f = pros_unsafe_err_u32!(
 			motor_get_flags,
 			err = DeviceError::errno_motor(),
 			self.get_port()
 		)?;
-		unimplemented!()
+		Ok(MotorFlags::from_bits_truncate(f))
+	}
+
+	/// Get a snapshot of the readings which describe the health of the motor.
+	///
+	/// # Examples
+	/// ```
+	/// println!("left drive: {}", motor.diagnostics()?);
+	/// ```
+	pub fn diagnostics(&self) -> Result<MotorDiagnostics, DeviceError> {
+		Ok(MotorDiagnostics {
+			temperature: self.get_temperature()?,
+			efficiency: self.get_efficiency()?,
+			power: self.get_power()?,
+			torque: self.get_torque()?,
+			current_draw: self.get_current_draw()?,
+			faults: self.get_faults()?,
+		})
 	}
 
+	/// Get the position of the motor in the currently configured
+	/// [`EncoderUnits`]. If the motor is reversed this is already negated by
+	/// PROS, so commanding a positive velocity or voltage always makes the
+	/// position increase.
 	pub fn get_position(&self) -> Result<f64, DeviceError> {
 		pros_unsafe_err_f!(
 			motor_get_position,
@@ -206,6 +328,70 @@ impl Motor {
 		)
 	}
 
+	/// Get the position of the motor in `units`, regardless of which
+	/// [`EncoderUnits`] the motor is currently configured to use.
+	pub fn get_position_in(&self, units: EncoderUnits) -> Result<f64, DeviceError> {
+		let gearing = self.get_gearing()?;
+		let current = self.get_encoder_units()?;
+		Ok(current.convert(self.get_position()?, units, gearing))
+	}
+
+	/// Get the position of the motor in degrees, regardless of the configured
+	/// [`EncoderUnits`].
+	#[inline]
+	pub fn get_position_degrees(&self) -> Result<f64, DeviceError> {
+		self.get_position_in(EncoderUnits::Degrees)
+	}
+
+	/// Get the position of the motor in full rotations, regardless of the
+	/// configured [`EncoderUnits`].
+	#[inline]
+	pub fn get_position_rotations(&self) -> Result<f64, DeviceError> {
+		self.get_position_in(EncoderUnits::Rotations)
+	}
+
+	/// Get the position of the motor in encoder ticks, regardless of the
+	/// configured [`EncoderUnits`]. See [`Gearset::ticks_per_rotation()`].
+	#[inline]
+	pub fn get_position_ticks(&self) -> Result<f64, DeviceError> {
+		self.get_position_in(EncoderUnits::Ticks)
+	}
+
+	/// Like [`Motor::move_absolute()`] except that `position` is given in
+	/// `units` instead of the currently configured [`EncoderUnits`].
+	///
+	/// # Examples
+	/// ```
+	/// motor.move_absolute_in(90.0, EncoderUnits::Degrees, 100)?;
+	/// ```
+	pub fn move_absolute_in(
+		&mut self,
+		position: f64,
+		units: EncoderUnits,
+		velocity: i32,
+	) -> Result<(), DeviceError> {
+		let gearing = self.get_gearing()?;
+		let current = self.get_encoder_units()?;
+		self.move_absolute(units.convert(position, current, gearing), velocity)
+	}
+
+	/// Check if the motor's position is within `tolerance` of `target`. The
+	/// position and tolerance are both in the currently configured
+	/// [`EncoderUnits`] for this motor, the bounds are inclusive.
+	///
+	/// # Examples
+	/// ```
+	/// motor.move_absolute(10.0, 100)?;
+	/// while !motor.at_position(10.0, 0.05)? {
+	/// 	Task::delay(Duration::from_millis(10));
+	/// }
+	/// ```
+	pub fn at_position(&self, target: f64, tolerance: f64) -> Result<bool, DeviceError> {
+		debug_assert!(tolerance >= 0.0);
+		let position = self.get_position()?;
+		Ok((position - target).abs() <= tolerance)
+	}
+
 	pub fn get_power(&self) -> Result<f64, DeviceError> {
 		pros_unsafe_err_f!(
 			motor_get_power,
@@ -406,6 +592,10 @@ impl Motor {
 		Ok(())
 	}
 
+	/// Set whether the motor is reversed. This is handled entirely by PROS, a
+	/// reversed motor negates every command sent to it as well as the position
+	/// and velocity read back from it, so from the point of view of the user a
+	/// reversed motor behaves the same as any other motor.
 	pub fn set_reversed(&mut self, reverse: bool) -> Result<(), DeviceError> {
 		pros_unsafe_err!(
 			motor_set_reversed,
@@ -437,6 +627,60 @@ impl Motor {
 		Ok(())
 	}
 
+	/// Get the constants of the motor's internal position PID controller.
+	pub fn get_position_pid(&self) -> Result<MotorPid, DeviceError> {
+		// On failure PROS fills every field with an error value which is also a
+		// valid constant, so errno is the only way to tell if this failed
+		clear_errno();
+		let pid = unsafe { motor_get_pos_pid(self.get_port()) };
+		if get_errno() != 0 {
+			Err(DeviceError::errno_motor())
+		} else {
+			Ok(pid.into())
+		}
+	}
+
+	/// Get the constants of the motor's internal velocity PID controller.
+	pub fn get_velocity_pid(&self) -> Result<MotorPid, DeviceError> {
+		clear_errno();
+		let pid = unsafe { motor_get_vel_pid(self.get_port()) };
+		if get_errno() != 0 {
+			Err(DeviceError::errno_motor())
+		} else {
+			Ok(pid.into())
+		}
+	}
+
+	/// Set the constants of the motor's internal position PID controller.
+	///
+	/// Changing these from their factory defaults may cause the motor to
+	/// behave unexpectedly, it is recommended to start from the values
+	/// returned by [`Motor::get_position_pid()`].
+	pub fn set_position_pid(&mut self, pid: MotorPid) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			motor_set_pos_pid_full,
+			err = DeviceError::errno_motor(),
+			self.get_port(),
+			pid.into()
+		)?;
+		Ok(())
+	}
+
+	/// Set the constants of the motor's internal velocity PID controller.
+	///
+	/// Changing these from their factory defaults may cause the motor to
+	/// behave unexpectedly, it is recommended to start from the values
+	/// returned by [`Motor::get_velocity_pid()`].
+	pub fn set_velocity_pid(&mut self, pid: MotorPid) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			motor_set_vel_pid_full,
+			err = DeviceError::errno_motor(),
+			self.get_port(),
+			pid.into()
+		)?;
+		Ok(())
+	}
+
 	pub fn tare_position(&mut self) -> Result<(), DeviceError> {
 		pros_unsafe_err!(
 			motor_tare_position,
@@ -445,6 +689,392 @@ impl Motor {
 		)?;
 		Ok(())
 	}
+
+	/// Actively hold the motor at its current position, such as to keep a lift
+	/// up without a driver holding a button. The brake mode is set to
+	/// [`BrakeMode::Hold`] and the motor is told to move to where it already
+	/// is, so it will push back if it is moved.
+	///
+	/// Call [`Motor::release()`] to stop holding and go back to the brake mode
+	/// from before this was called. Any other movement command will also stop
+	/// the hold, but will leave the brake mode as `Hold`.
+	///
+	/// # Examples
+	/// ```
+	/// if controller.get_button_new_press()?.contains(Buttons::A) {
+	/// 	lift.hold_here()?;
+	/// }
+	/// ```
+	pub fn hold_here(&mut self) -> Result<(), DeviceError> {
+		let position = self.get_position()?;
+		let velocity = self.get_gearing()?.max_velocity();
+		// Only remember the brake mode from before the first hold, holding
+		// again would otherwise have us restore to `Hold`
+		if self.released_brake_mode.is_none() {
+			self.released_brake_mode = Some(self.get_brake_mode()?);
+		}
+		self.set_brake_mode(BrakeMode::Hold)?;
+		self.move_absolute(position, velocity)
+	}
+
+	/// Stop holding the motor in place after [`Motor::hold_here()`], restoring
+	/// the brake mode it had before and stopping the motor. If the motor is not
+	/// being held this just stops it.
+	pub fn release(&mut self) -> Result<(), DeviceError> {
+		if let Some(mode) = self.released_brake_mode.take() {
+			self.set_brake_mode(mode)?;
+		}
+		self.stop()
+	}
+
+	/// Home a mechanism by driving it into a hard stop. The motor is driven at
+	/// `voltage`, in the same `[-127, 127]` range as [`Motor::move_simple()`],
+	/// until its current draw reaches `current_threshold` milliamps or until
+	/// `timeout` has passed. It is then stopped and its position tared to zero.
+	/// This blocks the current task until homing is complete,
+	/// see [`Motor::home_action()`] to home more than one mechanism at once.
+	///
+	/// Returns `true` if the hard stop was detected, or `false` if homing timed
+	/// out first. The position is tared either way.
+	///
+	/// # Examples
+	/// ```
+	/// if !lift.home(-30, 1500, Duration::from_secs(2))? {
+	/// 	println!("lift did not reach the bottom before timing out");
+	/// }
+	/// ```
+	pub fn home(
+		&mut self,
+		voltage: i8,
+		current_threshold: u32,
+		timeout: Duration,
+	) -> Result<bool, DeviceError> {
+		let mut home = self.home_action(voltage, current_threshold, timeout)?;
+		loop {
+			match home.poll() {
+				Poll::Complete(res) => return res,
+				Poll::Waiting => home.next().sleep(),
+			}
+		}
+	}
+
+	/// Start homing a mechanism in the same way as [`Motor::home()`], returning
+	/// an [`Action`] which completes once homing is done rather than blocking.
+	/// The action completes with `true` if the hard stop was detected, or
+	/// `false` if homing timed out first.
+	///
+	/// The current draw is ignored for a short time after starting, so the
+	/// motor accelerating is not mistaken for it stalling.
+	///
+	/// # Examples
+	/// ```
+	/// let mut lift_home = lift.home_action(-30, 1500, Duration::from_secs(2))?;
+	/// let mut claw_home = claw.home_action(20, 1000, Duration::from_secs(1))?;
+	/// let (mut lift_done, mut claw_done) = (false, false);
+	/// while !(lift_done && claw_done) {
+	/// 	action! {
+	/// 		res = lift_home => lift_done = res.is_ok(),
+	/// 		res = claw_home => claw_done = res.is_ok()
+	/// 	}
+	/// }
+	/// ```
+	pub fn home_action(
+		&mut self,
+		voltage: i8,
+		current_threshold: u32,
+		timeout: Duration,
+	) -> Result<impl Action<Output = Result<bool, DeviceError>> + '_, DeviceError> {
+		struct HomeAction<'a> {
+			motor: &'a mut Motor,
+			current_threshold: u32,
+			settled: Instant,
+			deadline: Instant,
+			done: bool,
+		}
+
+		impl<'a> HomeAction<'a> {
+			fn finish(&mut self, stalled: bool) -> Poll<Result<bool, DeviceError>> {
+				self.done = true;
+				let res = self
+					.motor
+					.stop()
+					.and_then(|_| self.motor.tare_position())
+					.map(|_| stalled);
+				Poll::Complete(res)
+			}
+		}
+
+		impl<'a> Action for HomeAction<'a> {
+			type Output = Result<bool, DeviceError>;
+
+			fn poll(&mut self) -> Poll<Self::Output> {
+				if self.done {
+					return Poll::Waiting;
+				}
+
+				let now = Instant::now();
+				if now < self.settled {
+					return Poll::Waiting;
+				}
+				match self.motor.get_current_draw() {
+					Ok(i) if i >= self.current_threshold => self.finish(true),
+					Ok(_) if now >= self.deadline => self.finish(false),
+					Ok(_) => Poll::Waiting,
+					Err(e) => {
+						self.done = true;
+						// Still try not to leave the motor driving into the stop
+						let _ = self.motor.stop();
+						Poll::Complete(Err(e))
+					}
+				}
+			}
+
+			fn next(&mut self) -> NextSleep {
+				if self.done {
+					NextSleep::Never
+				} else {
+					NextSleep::Timestamp(POLL_PERIOD)
+				}
+			}
+		}
+
+		self.move_simple(voltage)?;
+		let start = Instant::now();
+		Ok(HomeAction {
+			motor: self,
+			current_threshold,
+			settled: start + HOME_SETTLE_PERIOD,
+			deadline: start + timeout,
+			done: false,
+		})
+	}
+}
+
+/// Stop every motor which is plugged into the V5 Brain and set it to coast,
+/// whether or not there is a [`Motor`] for it. Ports with anything other than a
+/// motor plugged in are left alone.
+pub(crate) fn stop_all_motors() {
+	use crate::ports::DeviceType;
+
+	for port in 1..=21 {
+		let plugged: DeviceType = unsafe { registry_get_plugged_type(port - 1) }.into();
+		if plugged == DeviceType::Motor {
+			unsafe {
+				motor_set_brake_mode(port, BrakeMode::Coast.into());
+				motor_move_velocity(port, 0);
+			}
+		}
+	}
+}
+
+/// A group of motors which are all given the same commands, such as each side
+/// of a drivetrain or the motors of a lift.
+///
+/// Each motor keeps its own configuration, so motors which need to spin the
+/// other way should be reversed when they are created. Commands are sent to
+/// every motor in the group even if one of them fails, the first error is then
+/// returned.
+///
+/// # Examples
+/// ```
+/// let mut left = MotorGroup::from(vec![front_left, middle_left, back_left]);
+/// left.move_voltage(6000)?;
+/// ```
+#[derive(Debug)]
+pub struct MotorGroup {
+	pub motors: Vec<Motor>,
+}
+
+impl MotorGroup {
+	/// Create a new motor group from its motors.
+	pub fn new(motors: Vec<Motor>) -> Self {
+		MotorGroup { motors }
+	}
+
+	fn for_each(
+		&mut self,
+		mut f: impl FnMut(&mut Motor) -> Result<(), DeviceError>,
+	) -> Result<(), DeviceError> {
+		let mut result = Ok(());
+		for motor in self.motors.iter_mut() {
+			let r = f(motor);
+			if result.is_ok() {
+				result = r;
+			}
+		}
+		result
+	}
+
+	fn average(&self, f: impl Fn(&Motor) -> Result<f64, DeviceError>) -> Result<f64, DeviceError> {
+		if self.motors.is_empty() {
+			return Ok(0.0);
+		}
+		let mut sum = 0.0;
+		for motor in self.motors.iter() {
+			sum += f(motor)?;
+		}
+		Ok(sum / self.motors.len() as f64)
+	}
+
+	/// Set the voltage of every motor in the group, see
+	/// [`Motor::move_voltage()`].
+	pub fn move_voltage(&mut self, voltage: i16) -> Result<(), DeviceError> {
+		self.for_each(|m| m.move_voltage(voltage))
+	}
+
+	/// Set the velocity of every motor in the group, see
+	/// [`Motor::move_velocity()`].
+	pub fn move_velocity(&mut self, velocity: i32) -> Result<(), DeviceError> {
+		self.for_each(|m| m.move_velocity(velocity))
+	}
+
+	/// Set the brake mode of every motor in the group.
+	pub fn set_brake_mode(&mut self, mode: BrakeMode) -> Result<(), DeviceError> {
+		self.for_each(|m| m.set_brake_mode(mode))
+	}
+
+	/// Set the current position of every motor in the group as their zero
+	/// position.
+	pub fn tare_position(&mut self) -> Result<(), DeviceError> {
+		self.for_each(|m| m.tare_position())
+	}
+
+	/// Stop every motor in the group.
+	pub fn stop(&mut self) -> Result<(), DeviceError> {
+		self.for_each(|m| m.stop())
+	}
+
+	/// Get the average position of the motors in the group, in each motor's
+	/// own encoder units. An empty group has a position of 0.
+	pub fn get_average_position(&self) -> Result<f64, DeviceError> {
+		self.average(|m| m.get_position())
+	}
+
+	/// Get the average velocity of the motors in the group in RPM. An empty
+	/// group has a velocity of 0.
+	pub fn get_average_velocity(&self) -> Result<f64, DeviceError> {
+		self.average(|m| m.get_actual_velocity())
+	}
+}
+
+impl From<Vec<Motor>> for MotorGroup {
+	fn from(motors: Vec<Motor>) -> Self {
+		MotorGroup::new(motors)
+	}
+}
+
+/// The readings which describe the health of a motor, as returned by
+/// [`Motor::diagnostics()`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MotorDiagnostics {
+	/// The temperature of the motor in degrees Celsius,
+	pub temperature: f64,
+	/// The efficiency of the motor as a percentage,
+	pub efficiency: f64,
+	/// The power drawn by the motor in Watts,
+	pub power: f64,
+	/// The torque produced by the motor in Newton metres,
+	pub torque: f64,
+	/// The current drawn by the motor in milliamps,
+	pub current_draw: u32,
+	/// The faults which are currently occurring,
+	pub faults: FaultFlags,
+}
+
+/// Formats as a single line such as `45.0C 80.0% 2.10W 0.50Nm 1200mA
+/// OVER_TEMP`, with `ok` in place of the faults if there are none.
+impl fmt::Display for MotorDiagnostics {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{:.1}C {:.1}% {:.2}W {:.2}Nm {}mA ",
+			self.temperature, self.efficiency, self.power, self.torque, self.current_draw
+		)?;
+		if self.faults.is_empty() {
+			f.write_str("ok")
+		} else {
+			bitflags::parser::to_writer(&self.faults, f)
+		}
+	}
+}
+
+/// The constants of one of the motor's internal PID controllers.
+///
+/// PROS stores the constants as 4.4 fixed point numbers, which is the real
+/// value multiplied by 16, so a raw value of `16` is a gain of `1.0` and the
+/// smallest possible step is `0.0625`. Use [`MotorPid::to_fixed()`] and
+/// [`MotorPid::from_fixed()`] to convert between the two.
+///
+/// # Examples
+/// ```
+/// let mut pid = motor.get_velocity_pid()?;
+/// pid.kp = MotorPid::to_fixed(1.5);
+/// motor.set_velocity_pid(pid)?;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MotorPid {
+	/// The feedforward constant,
+	pub kf: u8,
+	/// The proportional constant,
+	pub kp: u8,
+	/// The integral constant,
+	pub ki: u8,
+	/// The derivative constant,
+	pub kd: u8,
+	/// A constant used for filtering the profile acceleration,
+	pub filter: u8,
+	/// The integral limit,
+	pub limit: u16,
+	/// The threshold for determining if a position movement has reached its
+	/// goal, this has no effect for the velocity controller,
+	pub threshold: u8,
+	/// The rate at which the controller is run in milliseconds,
+	pub loopspeed: u8,
+}
+
+impl MotorPid {
+	/// Convert a real value into the 4.4 fixed point representation used by
+	/// PROS. Values outside of what can be represented are saturated.
+	#[inline]
+	pub fn to_fixed(value: f64) -> u8 {
+		(value * 16.0).clamp(0.0, u8::MAX as f64) as u8
+	}
+
+	/// Convert a 4.4 fixed point value used by PROS into its real value.
+	#[inline]
+	pub fn from_fixed(value: u8) -> f64 {
+		value as f64 / 16.0
+	}
+}
+
+impl From<motor_pid_full_s> for MotorPid {
+	fn from(f: motor_pid_full_s) -> MotorPid {
+		MotorPid {
+			kf: f.kf,
+			kp: f.kp,
+			ki: f.ki,
+			kd: f.kd,
+			filter: f.filter,
+			limit: f.limit,
+			threshold: f.threshold,
+			loopspeed: f.loopspeed,
+		}
+	}
+}
+
+impl From<MotorPid> for motor_pid_full_s {
+	fn from(f: MotorPid) -> motor_pid_full_s {
+		motor_pid_full_s {
+			kf: f.kf,
+			kp: f.kp,
+			ki: f.ki,
+			kd: f.kd,
+			filter: f.filter,
+			limit: f.limit,
+			threshold: f.threshold,
+			loopspeed: f.loopspeed,
+		}
+	}
 }
 
 /// Describes the behavior of the motor when braking.
@@ -485,6 +1115,24 @@ pub enum EncoderUnits {
 	Ticks,
 }
 
+impl EncoderUnits {
+	/// Convert a position from these units into `to`. The gearing is needed to
+	/// know how many ticks there are per rotation.
+	pub fn convert(self, value: f64, to: EncoderUnits, gearing: Gearset) -> f64 {
+		let ticks = gearing.ticks_per_rotation() as f64;
+		let rotations = match self {
+			EncoderUnits::Degrees => value / 360.0,
+			EncoderUnits::Rotations => value,
+			EncoderUnits::Ticks => value / ticks,
+		};
+		match to {
+			EncoderUnits::Degrees => rotations * 360.0,
+			EncoderUnits::Rotations => rotations,
+			EncoderUnits::Ticks => rotations * ticks,
+		}
+	}
+}
+
 impl Default for EncoderUnits {
 	fn default() -> Self {
 		Self::Rotations
@@ -513,6 +1161,27 @@ pub enum Gearset {
 	Blue = 2,
 }
 
+impl Gearset {
+	/// The maximum velocity in RPM which can be commanded with this gearset.
+	pub const fn max_velocity(self) -> i32 {
+		match self {
+			Gearset::Red => 100,
+			Gearset::Green => 200,
+			Gearset::Blue => 600,
+		}
+	}
+
+	/// The amount of encoder ticks in one full rotation of the output shaft
+	/// with this gearset.
+	pub const fn ticks_per_rotation(self) -> u32 {
+		match self {
+			Gearset::Red => 1800,
+			Gearset::Green => 900,
+			Gearset::Blue => 300,
+		}
+	}
+}
+
 impl From<Gearset> for motor_gearset_e {
 	fn from(x: Gearset) -> Self {
 		match x {
@@ -532,6 +1201,7 @@ impl Default for Gearset {
 bitflags! {
 	/// Describes all possible faults that could be currently occurring
 	/// with the motor
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 	pub struct FaultFlags: u32 {
 		const NONE = 0x0;
 		/// Analogous to [`Motor::is_over_temp()`]
@@ -0,0 +1,246 @@
+use crate::bindings::*;
+use crate::devices::DeviceError;
+use crate::ports::Port;
+use crate::util::PROS_ERR_F;
+
+use core::time::Duration;
+
+// What `optical_get_gesture_raw` fills each field with when it fails
+const GESTURE_ERR: u8 = i8::MAX as u8;
+const GESTURE_COUNT_ERR: u16 = i16::MAX as u16;
+
+/// A struct which holds and presents a connected optical sensor connected to
+/// the V5 Brain.
+#[derive(Debug)]
+pub struct OpticalSensor {
+	pub port: Port,
+}
+
+/// The processed RGB values read from an optical sensor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgb {
+	pub red: f64,
+	pub green: f64,
+	pub blue: f64,
+	pub brightness: f64,
+}
+
+/// A gesture which has been detected by the optical sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+	/// No gesture has been detected.
+	None,
+	Up,
+	Down,
+	Right,
+	Left,
+}
+
+/// The raw data from the most recent gesture detected by the optical sensor,
+/// read with [`OpticalSensor::get_gesture_raw()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawGesture {
+	/// The magnitude of the gesture in the up direction.
+	pub up: u8,
+	/// The magnitude of the gesture in the down direction.
+	pub down: u8,
+	/// The magnitude of the gesture in the left direction.
+	pub left: u8,
+	/// The magnitude of the gesture in the right direction.
+	pub right: u8,
+	/// The type of the gesture as reported by the sensor.
+	pub gesture_type: u8,
+	/// The amount of gestures which have been detected.
+	pub count: u16,
+	/// The time since the sensor started when this gesture was detected.
+	pub time: Duration,
+}
+
+impl OpticalSensor {
+	/// Create a new optical sensor object. This will not call any SDK calls to
+	/// the optical sensor.
+	///
+	/// # Safety
+	/// There must only ever be a single reference to this sensor. It is up to
+	/// the caller to make sure there does not exists another device object with
+	/// the same port. If there is another device object with the same port this
+	/// will result in undefined behaviour and/or panics.
+	pub unsafe fn new(port: Port) -> Result<Self, DeviceError> {
+		let optical_sensor = Self { port };
+		Ok(optical_sensor)
+	}
+
+	#[inline]
+	pub fn get_port(&self) -> u8 {
+		self.port.get()
+	}
+
+	/// Get the detected colour hue in degrees, a value in the range of
+	/// `[0, 360)`.
+	pub fn get_hue(&self) -> Result<f64, DeviceError> {
+		pros_unsafe_err_f!(
+			optical_get_hue,
+			err = DeviceError::errno_optical(),
+			self.get_port()
+		)
+	}
+
+	/// Get the detected colour saturation, a value in the range of `[0, 1]`.
+	pub fn get_saturation(&self) -> Result<f64, DeviceError> {
+		pros_unsafe_err_f!(
+			optical_get_saturation,
+			err = DeviceError::errno_optical(),
+			self.get_port()
+		)
+	}
+
+	/// Get the detected colour brightness, a value in the range of `[0, 1]`.
+	pub fn get_brightness(&self) -> Result<f64, DeviceError> {
+		pros_unsafe_err_f!(
+			optical_get_brightness,
+			err = DeviceError::errno_optical(),
+			self.get_port()
+		)
+	}
+
+	/// Get the detected proximity value, a value in the range of `[0, 255]`
+	/// where a larger value means an object is closer.
+	pub fn get_proximity(&self) -> Result<i32, DeviceError> {
+		pros_unsafe_err!(
+			optical_get_proximity,
+			err = DeviceError::errno_optical(),
+			self.get_port()
+		)
+	}
+
+	/// Get the processed RGB values from the sensor.
+	pub fn get_rgb(&self) -> Result<Rgb, DeviceError> {
+		let res = unsafe { optical_get_rgb(self.get_port()) };
+		if res.red == PROS_ERR_F
+			&& res.green == PROS_ERR_F
+			&& res.blue == PROS_ERR_F
+			&& res.brightness == PROS_ERR_F
+		{
+			Err(DeviceError::errno_optical())
+		} else {
+			Ok(res.into())
+		}
+	}
+
+	/// Set the PWM value of the sensor's white LED, `value` must be in the
+	/// range of `[0, 100]`. This is enforced by a debug assertion and clamped
+	/// at runtime.
+	pub fn set_led_pwm(&mut self, value: u8) -> Result<(), DeviceError> {
+		debug_assert!(value <= 100);
+		pros_unsafe_err!(
+			optical_set_led_pwm,
+			err = DeviceError::errno_optical(),
+			self.get_port(),
+			value.clamp(0, 100)
+		)?;
+		Ok(())
+	}
+
+	/// Get the PWM value of the sensor's white LED, a value in the range of
+	/// `[0, 100]`.
+	pub fn get_led_pwm(&self) -> Result<i32, DeviceError> {
+		pros_unsafe_err!(
+			optical_get_led_pwm,
+			err = DeviceError::errno_optical(),
+			self.get_port()
+		)
+	}
+
+	/// Enable gesture detection on the sensor, this must be called before
+	/// [`OpticalSensor::get_gesture()`] will return any gestures.
+	///
+	/// The sensor can not detect gestures and colours at the same time, while
+	/// gestures are enabled the colour readings such as
+	/// [`OpticalSensor::get_hue()`] will not be updated. Call
+	/// [`OpticalSensor::disable_gesture()`] to go back to reading colours.
+	pub fn enable_gesture(&mut self) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			optical_enable_gesture,
+			err = DeviceError::errno_optical(),
+			self.get_port()
+		)?;
+		Ok(())
+	}
+
+	/// Disable gesture detection on the sensor, returning it to reading
+	/// colours.
+	pub fn disable_gesture(&mut self) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			optical_disable_gesture,
+			err = DeviceError::errno_optical(),
+			self.get_port()
+		)?;
+		Ok(())
+	}
+
+	/// Get the most recent gesture detected by the sensor. Gestures must first
+	/// be enabled with [`OpticalSensor::enable_gesture()`], until then this
+	/// will return [`Gesture::None`].
+	///
+	/// # Examples
+	/// ```
+	/// optical.enable_gesture()?;
+	/// while optical.get_gesture()? == Gesture::None {
+	/// 	Task::delay(Duration::from_millis(20));
+	/// }
+	/// // wave detected, start the routine
+	/// ```
+	pub fn get_gesture(&self) -> Result<Gesture, DeviceError> {
+		let g = unsafe { optical_get_gesture(self.get_port()) };
+		#[allow(non_upper_case_globals)]
+		match g {
+			optical_direction_e_NO_GESTURE => Ok(Gesture::None),
+			optical_direction_e_UP => Ok(Gesture::Up),
+			optical_direction_e_DOWN => Ok(Gesture::Down),
+			optical_direction_e_RIGHT => Ok(Gesture::Right),
+			optical_direction_e_LEFT => Ok(Gesture::Left),
+			optical_direction_e_ERROR => Err(DeviceError::errno_optical()),
+			_ => panic!(
+				"bindings::optical_get_gesture returned a value which is unknown to us: {}",
+				g
+			),
+		}
+	}
+
+	/// Get the raw data from the most recent gesture detected by the sensor,
+	/// including how strong it was in each direction. Gestures must first be
+	/// enabled with [`OpticalSensor::enable_gesture()`].
+	pub fn get_gesture_raw(&self) -> Result<RawGesture, DeviceError> {
+		let res = unsafe { optical_get_gesture_raw(self.get_port()) };
+		if res.udata == GESTURE_ERR && res.count == GESTURE_COUNT_ERR {
+			Err(DeviceError::errno_optical())
+		} else {
+			Ok(res.into())
+		}
+	}
+}
+
+impl From<optical_gesture_s> for RawGesture {
+	fn from(f: optical_gesture_s) -> RawGesture {
+		RawGesture {
+			up: f.udata,
+			down: f.ddata,
+			left: f.ldata,
+			right: f.rdata,
+			gesture_type: f.type_,
+			count: f.count,
+			time: Duration::from_millis(f.time as u64),
+		}
+	}
+}
+
+impl From<optical_rgb_s> for Rgb {
+	fn from(f: optical_rgb_s) -> Rgb {
+		Rgb {
+			red: f.red,
+			green: f.green,
+			blue: f.blue,
+			brightness: f.brightness,
+		}
+	}
+}
@@ -1,7 +1,11 @@
 use crate::bindings::*;
 use crate::devices::{DeviceError, Direction};
+use crate::math::Angle;
 use crate::ports::Port;
 
+/// The amount of centidegrees in a single full rotation.
+const CENTIDEGREES_PER_ROTATION: i32 = 36000;
+
 /// A struct which holds and presents a connected rotation sensor connected to
 /// the V5 Brain.
 #[derive(Debug)]
@@ -54,6 +58,32 @@ impl RotationSensor {
 		)
 	}
 
+	/// Set the rotation sensor absolute rotation value in centidegrees, this is
+	/// the value which is returned by [`RotationSensor::get_position()`].
+	pub fn set_position(&mut self, centidegrees: i32) -> Result<(), DeviceError> {
+		// PROS takes this as unsigned but passes it straight through to the
+		// sensor as signed, so negative positions survive the cast
+		pros_unsafe_err!(
+			rotation_set_position,
+			err = DeviceError::errno_rotation(),
+			self.get_port(),
+			centidegrees as u32
+		)?;
+		Ok(())
+	}
+
+	/// Get the rotation sensor's current position in degrees. See
+	/// [`RotationSensor::get_position()`].
+	pub fn get_position_degrees(&self) -> Result<f64, DeviceError> {
+		Ok(self.get_position()? as f64 / 100.0)
+	}
+
+	/// Get the rotation sensor's current position in full rotations. See
+	/// [`RotationSensor::get_position()`].
+	pub fn get_position_revolutions(&self) -> Result<f64, DeviceError> {
+		Ok(self.get_position()? as f64 / CENTIDEGREES_PER_ROTATION as f64)
+	}
+
 	/// Get the rotation sensor's current velocity in centidegrees per second.
 	pub fn get_velocity(&self) -> Result<i32, DeviceError> {
 		pros_unsafe_err!(
@@ -63,6 +93,12 @@ impl RotationSensor {
 		)
 	}
 
+	/// Get the rotation sensor's current velocity in rotations per minute.
+	pub fn get_velocity_rpm(&self) -> Result<f64, DeviceError> {
+		let centi = self.get_velocity()?;
+		Ok(centi as f64 * 60.0 / CENTIDEGREES_PER_ROTATION as f64)
+	}
+
 	/// Get the rotation sensor's current angle in centigrees, a value between 0
 	/// and 36000.
 	pub fn get_angle(&self) -> Result<i32, DeviceError> {
@@ -73,6 +109,27 @@ impl RotationSensor {
 		)
 	}
 
+	/// Get the rotation sensor's current angle as an [`Angle`], normalised to
+	/// the range `[0, 2π)`.
+	///
+	/// The sensor reports its angle in centidegrees, so the conversion is
+	/// `radians = centidegrees / 100 * π / 180`. A reading of 36000 is the same
+	/// position as 0 and is wrapped to [`Angle::ZERO`].
+	pub fn get_angle_typed(&self) -> Result<Angle, DeviceError> {
+		let centi = self.get_angle()?;
+		Ok(Angle::from_degrees(centi as f64 / 100.0).normalised())
+	}
+
+	/// Set the rotation sensor's absolute position from an [`Angle`]. The
+	/// angle is normalised to `[0, 2π)` and converted to centidegrees, rounding
+	/// to the nearest centidegree, before being passed to the sensor.
+	pub fn set_position_angle(&mut self, angle: Angle) -> Result<(), DeviceError> {
+		let centi = (angle.normalised().as_degrees() * 100.0 + 0.5) as i32;
+		// Rounding up from just below a full rotation would land on 36000,
+		// which is the same position as 0
+		self.set_position(centi % CENTIDEGREES_PER_ROTATION)
+	}
+
 	/// This will update the current direction in the rotation sensor to be
 	/// considered as the forwards direction. This will not reverse the
 	/// currently stored value in the sensor.
@@ -86,6 +143,18 @@ impl RotationSensor {
 		Ok(())
 	}
 
+	/// Swap which direction is considered as forward, this is the same as
+	/// calling [`RotationSensor::set_direction()`] with the opposite of
+	/// [`RotationSensor::get_direction()`].
+	pub fn reverse(&mut self) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			rotation_reverse,
+			err = DeviceError::errno_rotation(),
+			self.get_port()
+		)?;
+		Ok(())
+	}
+
 	/// Check which direction is currently considered as forward.
 	pub fn get_direction(&self) -> Result<Direction, DeviceError> {
 		let rev = pros_unsafe_err!(
@@ -0,0 +1,292 @@
+//! Printing and drawing to the V5 Brain's screen.
+//!
+//! This module contains simple text and shape drawing functions, as well as a
+//! scrolling logger which mirrors `println!` style output onto the brain's
+//! screen through the [`screen_logln!`] macro. This is useful for debugging on
+//! the field when the serial console is not available.
+//!
+//! Coordinates are measured in pixels from the top left corner of the screen,
+//! within the [`WIDTH`] and [`HEIGHT`] of the drawable area.
+
+use crate::bindings::*;
+use crate::devices::{Colour, DeviceError};
+use crate::rtos::{
+	tasks::{Task, TaskBuilder},
+	time::Instant,
+	Mutex, OnceCell,
+};
+use crate::util::to_cstring;
+
+use alloc::{collections::VecDeque, string::String};
+use core::{fmt, time::Duration};
+
+extern "C" {
+	// enum abi mismatch
+	pub(crate) fn screen_print(txt_fmt: u8, line: i16, text: *const core::ffi::c_char, ...) -> u32;
+}
+
+/// The maximum amount of lines of medium sized text which fit onto the screen.
+pub const MAX_LINES: u8 = 12;
+/// The width of the drawable area of the screen in pixels.
+pub const WIDTH: i16 = 480;
+/// The height of the drawable area of the screen in pixels.
+pub const HEIGHT: i16 = 240;
+
+/// Print a line of medium sized text onto the screen in the current pen
+/// colour, replacing the start of anything previously printed to that line.
+///
+/// # Debug Assertions
+/// Asserts that the line is within the [`MAX_LINES`] of the screen.
+pub fn print_line(line: u8, text: &str) -> Result<(), DeviceError> {
+	debug_assert!(line < MAX_LINES);
+	let text = to_cstring(String::from(text));
+	pros_unsafe_err_u32!(
+		screen_print,
+		err = DeviceError::errno_generic(),
+		text_format_e_t_E_TEXT_MEDIUM as _,
+		line as _,
+		cstr!("%s"),
+		text.as_ptr()
+	)?;
+	Ok(())
+}
+
+/// Clear the whole screen to the current eraser colour, which is black unless
+/// it has been changed.
+pub fn clear() -> Result<(), DeviceError> {
+	pros_unsafe_err_u32!(screen_erase, err = DeviceError::errno_generic())?;
+	Ok(())
+}
+
+/// Set the colour of the pen, this is the colour used for any text printed
+/// with [`print_line()`].
+pub fn set_pen(colour: Colour) -> Result<(), DeviceError> {
+	pros_unsafe_err_u32!(
+		screen_set_pen,
+		err = DeviceError::errno_generic(),
+		colour.as_u32()
+	)?;
+	Ok(())
+}
+
+/// Draw a single pixel in the given colour.
+pub fn draw_pixel(x: i16, y: i16, colour: Colour) -> Result<(), DeviceError> {
+	set_pen(colour)?;
+	pros_unsafe_err_u32!(screen_draw_pixel, err = DeviceError::errno_generic(), x, y)?;
+	Ok(())
+}
+
+/// Draw a line between two points in the given colour.
+pub fn draw_line(x0: i16, y0: i16, x1: i16, y1: i16, colour: Colour) -> Result<(), DeviceError> {
+	set_pen(colour)?;
+	pros_unsafe_err_u32!(
+		screen_draw_line,
+		err = DeviceError::errno_generic(),
+		x0,
+		y0,
+		x1,
+		y1
+	)?;
+	Ok(())
+}
+
+/// Draw the outline of a rectangle between two opposite corners in the given
+/// colour.
+pub fn draw_rect(x0: i16, y0: i16, x1: i16, y1: i16, colour: Colour) -> Result<(), DeviceError> {
+	set_pen(colour)?;
+	pros_unsafe_err_u32!(
+		screen_draw_rect,
+		err = DeviceError::errno_generic(),
+		x0,
+		y0,
+		x1,
+		y1
+	)?;
+	Ok(())
+}
+
+/// Draw a filled rectangle between two opposite corners in the given colour.
+pub fn fill_rect(x0: i16, y0: i16, x1: i16, y1: i16, colour: Colour) -> Result<(), DeviceError> {
+	set_pen(colour)?;
+	pros_unsafe_err_u32!(
+		screen_fill_rect,
+		err = DeviceError::errno_generic(),
+		x0,
+		y0,
+		x1,
+		y1
+	)?;
+	Ok(())
+}
+
+/// Configuration for the region of the screen used by [`screen_logln!`].
+#[derive(Debug, Clone, Copy)]
+pub struct LogConfig {
+	/// The first line of the screen the log region starts at.
+	pub first_line: u8,
+	/// How many lines the log region occupies, this is also how many of the
+	/// most recent log lines are kept.
+	pub lines: u8,
+	/// The minimum amount of time between redraws of the log region, lines
+	/// logged faster than this are drawn once this has passed.
+	pub redraw: Duration,
+}
+
+impl Default for LogConfig {
+	fn default() -> Self {
+		LogConfig {
+			first_line: 0,
+			lines: MAX_LINES,
+			redraw: Duration::from_millis(100),
+		}
+	}
+}
+
+struct ScreenLog {
+	config: LogConfig,
+	buffer: VecDeque<String>,
+	last_draw: Option<Instant>,
+	dirty: bool,
+}
+
+impl ScreenLog {
+	fn push(&mut self, line: &str) {
+		if self.config.lines == 0 {
+			return;
+		}
+		while self.buffer.len() >= self.config.lines as usize {
+			self.buffer.pop_front();
+		}
+		self.buffer.push_back(String::from(line));
+		self.dirty = true;
+	}
+
+	fn draw(&mut self) {
+		for i in 0..self.config.lines {
+			let text = self
+				.buffer
+				.get(i as usize)
+				.map(|s| s.as_str())
+				.unwrap_or("");
+			// Pad each line out so that whatever was previously drawn is
+			// overwritten, rather than erasing the region and flickering. The
+			// precision bounds the read so the text needs no NUL terminator
+			unsafe {
+				screen_print(
+					text_format_e_t_E_TEXT_MEDIUM as _,
+					(self.config.first_line + i) as _,
+					cstr!("%-60.*s"),
+					text.len() as core::ffi::c_int,
+					text.as_ptr(),
+				);
+			}
+		}
+		self.last_draw = Some(Instant::now());
+		self.dirty = false;
+	}
+
+	fn should_draw(&self) -> bool {
+		self.dirty
+			&& self
+				.last_draw
+				.map(|t| t.elapsed() >= self.config.redraw)
+				.unwrap_or(true)
+	}
+}
+
+static LOG: OnceCell<Mutex<ScreenLog>> = OnceCell::new();
+
+fn log() -> &'static Mutex<ScreenLog> {
+	LOG.call_once(|| {
+		spawn_flush_task();
+		Mutex::new(ScreenLog {
+			config: LogConfig::default(),
+			buffer: VecDeque::new(),
+			last_draw: None,
+			dirty: false,
+		})
+	});
+	LOG.wait()
+}
+
+/// The shortest time the flush task will sleep for, so a zero redraw period
+/// does not keep it busy.
+const FLUSH_MIN_PERIOD: Duration = Duration::from_millis(10);
+
+/// Spawn the task which draws lines that were throttled, so the most recent
+/// line always reaches the screen even if nothing is logged after it.
+fn spawn_flush_task() {
+	// Without the task lines are still drawn by the next log or flush, so
+	// failing to spawn it is not worth panicking in the middle of logging
+	let _ = TaskBuilder::new()
+		.name(String::from("screen_log"))
+		.priority(Task::PRIORITY_MIN)
+		.spawn(|| loop {
+			let period = {
+				let mut log = log().lock();
+				if log.should_draw() {
+					log.draw();
+				}
+				log.config.redraw
+			};
+			Task::delay(period.max(FLUSH_MIN_PERIOD));
+		});
+}
+
+/// Change the region of the screen used by [`screen_logln!`]. Lines which no
+/// longer fit in the region are discarded.
+///
+/// # Debug Assertions
+/// Asserts that the region fits within the [`MAX_LINES`] of the screen.
+pub fn configure_log(config: LogConfig) {
+	debug_assert!(config.first_line as u32 + config.lines as u32 <= MAX_LINES as u32);
+	let mut log = log().lock();
+	log.config = config;
+	while log.buffer.len() > config.lines as usize {
+		log.buffer.pop_front();
+	}
+	log.dirty = true;
+}
+
+/// Redraw the log region immediately if there are any lines which have not
+/// yet been drawn because of the redraw throttling.
+pub fn flush_log() {
+	let mut log = log().lock();
+	if log.dirty {
+		log.draw();
+	}
+}
+
+#[doc(hidden)]
+pub fn log_fmt(args: fmt::Arguments) {
+	let text = alloc::fmt::format(args);
+	let mut log = log().lock();
+	for line in text.split('\n') {
+		log.push(line);
+	}
+	if log.should_draw() {
+		log.draw();
+	}
+}
+
+/// Append a line to the scrolling log region on the brain's screen. The
+/// arguments are formatted the same way as `println!`.
+///
+/// Only the most recent lines which fit in the region set by
+/// [`configure_log()`] are kept. Redraws are throttled to avoid flickering, use
+/// [`flush_log()`] to force any pending lines to be drawn.
+///
+/// # Examples
+/// ```
+/// screen_logln!(
+/// 	"left: {}, right: {}",
+/// 	left.get_position()?,
+/// 	right.get_position()?
+/// );
+/// ```
+#[macro_export]
+macro_rules! screen_logln {
+	($($arg:tt)*) => {
+		$crate::devices::screen::log_fmt(format_args!($($arg)*))
+	};
+}
@@ -0,0 +1,112 @@
+use crate::bindings::*;
+use crate::devices::DeviceError;
+use crate::ports::Port;
+
+/// A struct which holds and presents a V5 smart port which has been configured
+/// as a generic serial port. This can be used to communicate with a
+/// co-processor connected to the smart port.
+///
+/// Reads and writes are non-blocking, they only transfer as many bytes as are
+/// currently available in the internal buffers.
+#[derive(Debug)]
+pub struct SerialPort {
+	pub port: Port,
+}
+
+impl SerialPort {
+	/// Create a new serial port with the specified port and baudrate. This will
+	/// configure the smart port as a generic serial port.
+	///
+	/// # Safety
+	/// There must only ever be a single reference to this port. It is up to
+	/// the caller to make sure there does not exists another device object with
+	/// the same port. If there is another device object with the same port this
+	/// will result in undefined behaviour and/or panics.
+	pub unsafe fn new(port: Port, baudrate: u32) -> Result<Self, DeviceError> {
+		let mut s = SerialPort { port };
+		pros_unsafe_err!(
+			serial_enable,
+			err = DeviceError::errno_generic(),
+			s.get_port()
+		)?;
+		s.set_baudrate(baudrate)?;
+		Ok(s)
+	}
+
+	#[inline]
+	pub fn get_port(&self) -> u8 {
+		self.port.get()
+	}
+
+	/// Change the baudrate of the serial port.
+	pub fn set_baudrate(&mut self, baudrate: u32) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			serial_set_baudrate,
+			err = DeviceError::errno_generic(),
+			self.get_port(),
+			baudrate as i32
+		)?;
+		Ok(())
+	}
+
+	/// Get the amount of bytes which have been received and are ready to be
+	/// read.
+	pub fn bytes_available(&self) -> Result<usize, DeviceError> {
+		pros_unsafe_err!(
+			serial_get_read_avail,
+			err = DeviceError::errno_generic(),
+			self.get_port()
+		)
+		.map(|n| n as usize)
+	}
+
+	/// Get the amount of bytes which can currently be written before the
+	/// output buffer is full.
+	pub fn write_free(&self) -> Result<usize, DeviceError> {
+		pros_unsafe_err!(
+			serial_get_write_free,
+			err = DeviceError::errno_generic(),
+			self.get_port()
+		)
+		.map(|n| n as usize)
+	}
+
+	/// Read as many bytes as are available into `buf`, returning how many
+	/// bytes were read. This does not block, if nothing has been received `0`
+	/// is returned.
+	pub fn read(&mut self, buf: &mut [u8]) -> Result<usize, DeviceError> {
+		pros_unsafe_err!(
+			serial_read,
+			err = DeviceError::errno_generic(),
+			self.get_port(),
+			buf.as_mut_ptr(),
+			buf.len() as i32
+		)
+		.map(|n| n as usize)
+	}
+
+	/// Write as many bytes from `buf` as will fit into the output buffer,
+	/// returning how many bytes were written. This does not block, so it may
+	/// write less than all of `buf`.
+	pub fn write(&mut self, buf: &[u8]) -> Result<usize, DeviceError> {
+		pros_unsafe_err!(
+			serial_write,
+			err = DeviceError::errno_generic(),
+			self.get_port(),
+			buf.as_ptr() as *mut u8,
+			buf.len() as i32
+		)
+		.map(|n| n as usize)
+	}
+
+	/// Clear the internal input and output buffers of the serial port. Any
+	/// bytes which have not yet been read or sent are discarded.
+	pub fn flush(&mut self) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			serial_flush,
+			err = DeviceError::errno_generic(),
+			self.get_port()
+		)?;
+		Ok(())
+	}
+}
@@ -0,0 +1,161 @@
+//! Reading and writing files on the micro SD card inserted into the V5 Brain.
+//!
+//! Files are accessed through the C standard library provided by PROS. All
+//! paths are relative to the root of the SD card, the `/usd/` prefix which
+//! PROS requires is added automatically.
+
+use crate::bindings::*;
+use crate::util::{get_errno, to_cstring};
+
+use alloc::{format, string::String, vec::Vec};
+use core::ffi::{c_char, c_int, c_void};
+use core::fmt;
+
+extern "C" {
+	fn fopen(path: *const c_char, mode: *const c_char) -> *mut c_void;
+	fn fclose(file: *mut c_void) -> c_int;
+	fn fread(ptr: *mut c_void, size: usize, count: usize, file: *mut c_void) -> usize;
+	fn fwrite(ptr: *const c_void, size: usize, count: usize, file: *mut c_void) -> usize;
+	fn fflush(file: *mut c_void) -> c_int;
+	fn ferror(file: *mut c_void) -> c_int;
+}
+
+/// Possible errors that could be returned when accessing files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileError {
+	/// There is no SD card inserted into the V5 Brain,
+	NotInstalled,
+	/// The file which was opened does not exist,
+	NotFound,
+	/// The file could not be read from or written to,
+	Io,
+}
+
+impl FileError {
+	fn errno() -> Self {
+		if !is_installed() {
+			return Self::NotInstalled;
+		}
+		match get_errno() {
+			libc::ENOENT => Self::NotFound,
+			_ => Self::Io,
+		}
+	}
+}
+
+impl fmt::Display for FileError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::NotInstalled => "no SD card is inserted",
+			Self::NotFound => "file not found",
+			Self::Io => "file could not be read from or written to",
+		})
+	}
+}
+
+impl core::error::Error for FileError {}
+
+/// Check if there is an SD card inserted into the V5 Brain.
+pub fn is_installed() -> bool {
+	unsafe { usd_is_installed() == 1 }
+}
+
+/// An open file on the SD card. The file is closed when this is dropped.
+///
+/// # Examples
+/// ```
+/// let mut log = File::create("auton.csv")?;
+/// log.write_all(b"time,left,right\n")?;
+/// ```
+#[derive(Debug)]
+pub struct File {
+	ptr: *mut c_void,
+}
+
+impl File {
+	/// Open a file for writing, creating it if it does not exist and truncating
+	/// it if it does.
+	pub fn create(path: &str) -> Result<File, FileError> {
+		Self::open_with(path, cstr!("w"))
+	}
+
+	/// Open a file for writing, creating it if it does not exist. Anything
+	/// written is added onto the end of the file.
+	pub fn append(path: &str) -> Result<File, FileError> {
+		Self::open_with(path, cstr!("a"))
+	}
+
+	/// Open an existing file for reading.
+	pub fn open(path: &str) -> Result<File, FileError> {
+		Self::open_with(path, cstr!("r"))
+	}
+
+	fn open_with(path: &str, mode: *const c_char) -> Result<File, FileError> {
+		if !is_installed() {
+			return Err(FileError::NotInstalled);
+		}
+		let path = to_cstring(usd_path(path));
+		let ptr = unsafe { fopen(path.as_ptr() as _, mode) };
+		if ptr.is_null() {
+			Err(FileError::errno())
+		} else {
+			Ok(File { ptr })
+		}
+	}
+
+	/// Write all of `buf` to the file.
+	pub fn write_all(&mut self, buf: &[u8]) -> Result<(), FileError> {
+		let written = unsafe { fwrite(buf.as_ptr() as _, 1, buf.len(), self.ptr) };
+		if written == buf.len() {
+			Ok(())
+		} else {
+			Err(FileError::errno())
+		}
+	}
+
+	/// Read the rest of the file, appending it onto `buf`. Returns the amount
+	/// of bytes which were read.
+	pub fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize, FileError> {
+		const CHUNK: usize = 512;
+		let start = buf.len();
+		loop {
+			buf.reserve(CHUNK);
+			let len = buf.len();
+			let read = unsafe { fread(buf.as_mut_ptr().add(len) as _, 1, CHUNK, self.ptr) };
+			// SAFETY: fread has initialised this many bytes past the old length
+			unsafe { buf.set_len(len + read) };
+			if read < CHUNK {
+				break;
+			}
+		}
+		if unsafe { ferror(self.ptr) } != 0 {
+			Err(FileError::Io)
+		} else {
+			Ok(buf.len() - start)
+		}
+	}
+
+	/// Make sure everything written to the file so far has been written out to
+	/// the SD card.
+	pub fn flush(&mut self) -> Result<(), FileError> {
+		if unsafe { fflush(self.ptr) } == 0 {
+			Ok(())
+		} else {
+			Err(FileError::errno())
+		}
+	}
+}
+
+impl Drop for File {
+	fn drop(&mut self) {
+		unsafe { fclose(self.ptr) };
+	}
+}
+
+fn usd_path(path: &str) -> String {
+	if path.starts_with("/usd/") {
+		String::from(path)
+	} else {
+		format!("/usd/{}", path.trim_start_matches('/'))
+	}
+}
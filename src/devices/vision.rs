@@ -5,6 +5,8 @@ use crate::devices::DeviceError;
 use crate::ports::Port;
 use crate::util::PROS_ERR_VISION_OBJECT_SIG;
 
+use core::fmt;
+
 /// A struct which holds and presents a connected VEX Vision sensor connected
 /// to the V5 Brain.
 pub struct Vision {
@@ -136,7 +138,7 @@ impl Vision {
 	pub fn set_exposure(&mut self, exposure: u8) -> Result<(), DeviceError> {
 		debug_assert!(exposure <= 150);
 		pros_unsafe_err!(
-			vision_set_white_balance,
+			vision_set_exposure,
 			err = DeviceError::errno_vision(),
 			self.get_port(),
 			exposure.clamp(0, 150) as _
@@ -289,6 +291,89 @@ impl Vision {
 		// C bindings will add the objects but not incrememnt the length
 		unsafe { vec.set_len(num_detected as usize) }
 
+		Ok(Self::objects_from_raw(vec))
+	}
+
+	/// Read up to `object_count` of the largest objects matching `sig_id`, the
+	/// same as [`Vision::read_by_sig()`] but dropping any objects with an
+	/// [`Object::area()`] smaller than `min_area`. This is useful for ignoring
+	/// small false detections.
+	///
+	/// The objects are sorted by their area from largest to smallest, so the
+	/// first object is always the largest.
+	///
+	/// # Examples
+	/// ```
+	/// let goals = camera.read_by_sig_filtered(1, 200, 4)?;
+	/// if let Some(goal) = goals.first() {
+	/// 	println!("largest goal is {} pixels", goal.area());
+	/// }
+	/// ```
+	pub fn read_by_sig_filtered(
+		&self,
+		sig_id: u32,
+		min_area: i32,
+		object_count: u32,
+	) -> Result<SmallVec<[Object; 4]>, DeviceError> {
+		let mut objects = self.read_by_sig(0, sig_id, object_count)?;
+		objects.retain(|obj| obj.area() >= min_area);
+		objects.sort_unstable_by_key(|obj| core::cmp::Reverse(obj.area()));
+		Ok(objects)
+	}
+
+	/// Read up to `object_count` objects matching `colour_code`, starting from
+	/// the `size_id`th largest object.
+	pub fn read_by_code(
+		&self,
+		size_id: u32,
+		colour_code: ColourCode,
+		object_count: u32,
+	) -> Result<SmallVec<[Object; 4]>, DeviceError> {
+		let mut vec: SmallVec<[vision_object_s_t; 4]> =
+			SmallVec::with_capacity(object_count as usize);
+
+		let num_detected = pros_unsafe_err!(
+			vision_read_by_code,
+			err = DeviceError::errno_vision(),
+			self.get_port(),
+			size_id,
+			colour_code.as_raw(),
+			object_count,
+			vec.as_mut_ptr()
+		)?;
+
+		// see `read_by_sig`
+		unsafe { vec.set_len(num_detected as usize) }
+
+		Ok(Self::objects_from_raw(vec))
+	}
+
+	/// Read up to `object_count` objects of any signature, starting from the
+	/// `size_id`th largest object.
+	pub fn read_by_size(
+		&self,
+		size_id: u32,
+		object_count: u32,
+	) -> Result<SmallVec<[Object; 4]>, DeviceError> {
+		let mut vec: SmallVec<[vision_object_s_t; 4]> =
+			SmallVec::with_capacity(object_count as usize);
+
+		let num_detected = pros_unsafe_err!(
+			vision_read_by_size,
+			err = DeviceError::errno_vision(),
+			self.get_port(),
+			size_id,
+			object_count,
+			vec.as_mut_ptr()
+		)?;
+
+		// see `read_by_sig`
+		unsafe { vec.set_len(num_detected as usize) }
+
+		Ok(Self::objects_from_raw(vec))
+	}
+
+	fn objects_from_raw(vec: SmallVec<[vision_object_s_t; 4]>) -> SmallVec<[Object; 4]> {
 		let vec = vec
 			.into_iter()
 			.map(Object::from_raw)
@@ -297,7 +382,7 @@ impl Vision {
 		// this should never fail since pros should always return a valid
 		// vision_object_s_t
 		if let Some(vec) = vec {
-			Ok(vec)
+			vec
 		} else {
 			unreachable!()
 		}
@@ -340,6 +425,40 @@ impl Object {
 			middle_coord: (raw.x_middle_coord, raw.y_middle_coord),
 		})
 	}
+
+	/// The area of the bounding box of this object in pixels.
+	#[inline]
+	pub fn area(&self) -> i32 {
+		self.width as i32 * self.height as i32
+	}
+
+	/// How far the middle of this object is from the centre of the camera's
+	/// view horizontally, from `-1.0` (left edge) to `1.0` (right edge).
+	///
+	/// This assumes the zero point is [`ZeroPoint::Centre`], the default used
+	/// by [`Vision::new()`].
+	///
+	/// # Examples
+	/// ```
+	/// let goals = camera.read_by_sig_filtered(1, 200, 1)?;
+	/// if let Some(goal) = goals.first() {
+	/// 	let turn = goal.x_offset_from_center() * 0.5;
+	/// }
+	/// ```
+	#[inline]
+	pub fn x_offset_from_center(&self) -> f64 {
+		(self.middle_coord.0 as f64 / (Vision::FOV_WIDTH / 2.0)).clamp(-1.0, 1.0)
+	}
+
+	/// How far the middle of this object is from the centre of the camera's
+	/// view vertically, from `-1.0` (top edge) to `1.0` (bottom edge).
+	///
+	/// This assumes the zero point is [`ZeroPoint::Centre`], the default used
+	/// by [`Vision::new()`].
+	#[inline]
+	pub fn y_offset_from_center(&self) -> f64 {
+		(self.middle_coord.1 as f64 / (Vision::FOV_HEIGHT / 2.0)).clamp(-1.0, 1.0)
+	}
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -388,9 +507,81 @@ impl Signature {
 	pub fn get_id(&self) -> SignatureId {
 		SignatureId::from_u8(self.0.id).unwrap_or_else(|| unreachable!())
 	}
+
+	/// Print the signature to the terminal in a form which can be copied
+	/// straight into source code.
+	pub fn print(&self) {
+		unsafe { vision_print_signature(self.0) };
+	}
+
+	/// The lower bound of the U (blue difference) chroma component, in the
+	/// sensor's raw YUV units, which are signed and centred on zero.
+	pub fn u_min(&self) -> i32 {
+		self.0.u_min
+	}
+
+	/// The upper bound of the U chroma component, in the same units as
+	/// [`Signature::u_min()`].
+	pub fn u_max(&self) -> i32 {
+		self.0.u_max
+	}
+
+	/// The average U chroma component of the colour the signature was trained
+	/// on, between [`Signature::u_min()`] and [`Signature::u_max()`].
+	pub fn u_mean(&self) -> i32 {
+		self.0.u_mean
+	}
+
+	/// The lower bound of the V (red difference) chroma component, in the
+	/// sensor's raw YUV units, which are signed and centred on zero.
+	pub fn v_min(&self) -> i32 {
+		self.0.v_min
+	}
+
+	/// The upper bound of the V chroma component, in the same units as
+	/// [`Signature::v_min()`].
+	pub fn v_max(&self) -> i32 {
+		self.0.v_max
+	}
+
+	/// The average V chroma component of the colour the signature was trained
+	/// on, between [`Signature::v_min()`] and [`Signature::v_max()`].
+	pub fn v_mean(&self) -> i32 {
+		self.0.v_mean
+	}
+
+	/// How far outside of the U and V bounds a colour may be and still match,
+	/// as the unitless scale factor set in the Vision Utility, commonly 3.0.
+	pub fn range(&self) -> f32 {
+		self.0.range
+	}
+
+	/// The raw type of the signature as set by the Vision Utility, which tells
+	/// normal colour signatures apart from those used in colour codes.
+	pub fn sig_type(&self) -> i32 {
+		self.0.type_ as i32
+	}
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+/// Formats all of the fields in the same order as [`Signature::create()`]
+/// takes them.
+impl fmt::Debug for Signature {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Signature")
+			.field("id", &self.get_id())
+			.field("u_min", &self.u_min())
+			.field("u_max", &self.u_max())
+			.field("u_mean", &self.u_mean())
+			.field("v_min", &self.v_min())
+			.field("v_max", &self.v_max())
+			.field("v_mean", &self.v_mean())
+			.field("range", &self.range())
+			.field("sig_type", &self.sig_type())
+			.finish()
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum SignatureId {
 	Sig1 = 1,
 	Sig2,
@@ -26,6 +26,13 @@
 //! }
 //! robot!(VexRobot);
 //! ```
+//!
+//! # Imports
+//! The most commonly used types are all re-exported from [`prelude`]. Every
+//! device lives in its own module under [`devices`], such as
+//! [`devices::motor::Motor`] or [`devices::imu::IMU`], and all of them report
+//! errors with [`devices::DeviceError`]. Ports and TriPorts are found in
+//! [`ports`], while tasks, synchronisation and timing are found in [`rtos`].
 
 extern crate alloc;
 #[macro_use]
@@ -38,9 +45,10 @@ mod bindings {
 #[doc(hidden)]
 #[macro_use]
 pub mod macros;
-mod util;
+pub mod util;
 
 pub mod devices;
+pub use pros_math as math;
 pub mod ports;
 pub mod prelude {
 	//! Common types and macros that can all be conveniently imported at once.
@@ -49,17 +57,19 @@ pub mod prelude {
 	pub use crate::ports::*;
 	pub use crate::rtos::{
 		action::{Action, NextSleep, Poll},
-		tasks::{CompetitionState, CompetitionTask, Task},
+		tasks::{CompetitionState, CompetitionTask, MatchInfo, Task},
 		time::{Instant, Interval},
-		Mutex,
+		Mutex, RwLock,
 	};
 	pub use crate::Robot;
-	pub use crate::{action, robot};
+	pub use crate::{action, log, log_info, log_warn, robot, screen_logln};
 	pub use alloc::vec::Vec;
 	pub use core::time::Duration;
 	pub use libc_print::std_name::*;
 }
 pub mod rtos;
+pub mod safety;
+pub mod subsystems;
 
 /// This trait is used so that `pros-rs` knows which functions it should call
 /// for the tasks that are addressed out by the competition manager.
@@ -95,9 +105,16 @@ pub trait Robot {
 	fn opcontrol(&'static self, state: rtos::tasks::CompetitionState) {}
 }
 
-// TODO: Printing to screen for easy debugging
+/// How many characters of medium text fit on one line of the screen.
+const PANIC_SCREEN_WIDTH: usize = 46;
+/// How many lines of the screen the panic message can take up, leaving the
+/// first line for the location.
+const PANIC_SCREEN_LINES: u8 = 10;
+
 #[panic_handler]
 fn panic(info: &core::panic::PanicInfo) -> ! {
+	use core::fmt::Write;
+
 	// Print main text
 	libc_print::libc_eprint!("task panicked at ");
 	// Print panic message
@@ -115,43 +132,42 @@ fn panic(info: &core::panic::PanicInfo) -> ! {
 	}
 	libc_print::libc_eprintln!();
 
-	// TODO: format panic message for screen
-	//	screen_print_at(0, cstr!("panicked!"));
-
+	// Everything is formatted on the stack, as the panic could have been caused
+	// by running out of memory
+	let mut header = util::StackString::<64>::new();
 	if let Some(s) = info.location() {
-		screen_print_at(
-			0,
-			alloc::ffi::CString::new(alloc::format!(
-				"panicked at {}:{}",
-				s.line(),
-				&s.file()[s.file().len() - 10..]
-			))
-			.unwrap()
-			.as_c_str()
-			.as_ptr() as _,
-		);
+		let file = s.file().rsplit(['/', '\\']).next().unwrap_or(s.file());
+		_ = write!(header, "panicked at {}:{}", file, s.line());
 	} else {
-		screen_print_at(0, cstr!("panicked"));
+		_ = header.write_str("panicked");
 	}
-
-	unsafe {
-		// Go through and stop motors regardless if they are actually motors or not
-		for i in 1..21 {
-			bindings::motor_set_brake_mode(i, devices::motor::BrakeMode::Coast.into());
-			bindings::motor_move_velocity(i, 0);
+	screen_print_at(0, header.as_c_str().as_ptr() as _);
+
+	// Split the message over as many lines as it needs, anything which does
+	// not fit is still printed in full to the terminal above
+	let mut message = util::StackString::<256>::new();
+	_ = write!(message, "{}", info.message());
+	let mut rest = message.as_str();
+	let mut line = 1;
+	while !rest.is_empty() && line <= PANIC_SCREEN_LINES {
+		let mut take = rest.len().min(PANIC_SCREEN_WIDTH);
+		while !rest.is_char_boundary(take) {
+			take -= 1;
 		}
+		let mut text = util::StackString::<{ PANIC_SCREEN_WIDTH + 1 }>::new();
+		_ = text.write_str(&rest[..take]);
+		screen_print_at(line, text.as_c_str().as_ptr() as _);
+		rest = &rest[take..];
+		line += 1;
 	}
-	loop {}
-}
 
-extern "C" {
-	// enum abi mismatch
-	fn screen_print(txt_fmt: u8, line: i16, text: *const core::ffi::c_char, ...) -> u32;
+	devices::motor::stop_all_motors();
+	loop {}
 }
 
 pub fn screen_print_at(line: u8, msg: *const u8) {
 	unsafe {
-		screen_print(
+		devices::screen::screen_print(
 			bindings::text_format_e_t_E_TEXT_MEDIUM as _,
 			line as _,
 			cstr!("%s") as *const _,
@@ -37,6 +37,7 @@ macro_rules! robot {
 			use $crate::{rtos::tasks, Robot};
 
 			let robot = ROBOT.wait();
+			robot.1.start_phase();
 			robot.0.disabled(robot.1.clone());
 		}
 
@@ -46,6 +47,7 @@ macro_rules! robot {
 			use $crate::{rtos::tasks, Robot};
 
 			let robot = ROBOT.wait();
+			robot.1.start_phase();
 			robot.0.competition_init(robot.1.clone());
 		}
 
@@ -54,6 +56,7 @@ macro_rules! robot {
 		extern "C" fn autonomous() {
 			use $crate::{rtos::tasks, Robot};
 			let robot = ROBOT.wait();
+			robot.1.start_phase();
 			robot.1.add_autonomous(tasks::Task::current());
 
 			tasks::spawn(|| {
@@ -67,6 +70,7 @@ macro_rules! robot {
 		extern "C" fn opcontrol() {
 			use $crate::{rtos::tasks, Robot};
 			let robot = ROBOT.wait();
+			robot.1.start_phase();
 			robot.1.add_opcontrol(tasks::Task::current());
 
 			tasks::spawn(|| {
@@ -80,8 +84,88 @@ macro_rules! robot {
 #[doc(hidden)]
 pub use pros_macros::action_internal;
 
+/// Print a line to the serial console prefixed with the time in milliseconds
+/// since the program started and the name of the task which printed it. The
+/// arguments are formatted the same way as `println!`. This is the same as
+/// [`log_info!`][crate::log_info].
+///
+/// Logging can be quietened at compile time, enabling the `log-level-warn`
+/// feature only prints [`log_warn!`][crate::log_warn] lines and enabling
+/// `log-level-off` prints nothing at all.
+///
+/// # Examples
+/// ```
+/// log!("lift at {}", lift.get_position()?);
+/// // prints "[   12840] INFO lift: lift at 1.25"
+/// ```
+#[macro_export]
+macro_rules! log {
+	($($arg:tt)*) => {
+		$crate::log_info!($($arg)*)
+	};
+}
+
+/// Print a line to the serial console in the same way as [`log!`][crate::log],
+/// marked as information.
+#[macro_export]
+macro_rules! log_info {
+	($($arg:tt)*) => {
+		$crate::macros::log_fmt($crate::macros::LogLevel::Info, format_args!($($arg)*))
+	};
+}
+
+/// Print a line to the serial console in the same way as [`log!`][crate::log],
+/// marked as a warning.
+#[macro_export]
+macro_rules! log_warn {
+	($($arg:tt)*) => {
+		$crate::macros::log_fmt($crate::macros::LogLevel::Warn, format_args!($($arg)*))
+	};
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+	Info,
+	Warn,
+}
+
+// The features are checked here rather than in the macros, where they would
+// be checked against the features of the crate using them
+const MIN_LOG_LEVEL: Option<LogLevel> = if cfg!(feature = "log-level-off") {
+	None
+} else if cfg!(feature = "log-level-warn") {
+	Some(LogLevel::Warn)
+} else {
+	Some(LogLevel::Info)
+};
+
+#[doc(hidden)]
+pub fn log_fmt(level: LogLevel, args: core::fmt::Arguments) {
+	if !MIN_LOG_LEVEL.is_some_and(|min| level >= min) {
+		return;
+	}
+
+	let tag = match level {
+		LogLevel::Info => "INFO",
+		LogLevel::Warn => "WARN",
+	};
+	let mut task = crate::rtos::tasks::Task::current();
+	libc_print::libc_println!(
+		"[{:>8}] {} {}: {}",
+		crate::rtos::time::Instant::now().as_millis(),
+		tag,
+		task.name(),
+		args
+	);
+}
+
 /// Poll actions until one is found to be complete, if none are complete, then
 /// it will sleep until the next time that one is suggested to be available.
+/// If every action returns
+/// [`NextSleep::Never`][crate::rtos::action::NextSleep::Never] the task sleeps
+/// for a millisecond instead, so a loop around this macro will not starve
+/// other tasks, including those with a lower priority.
 ///
 /// # Examples
 /// It is common to use this in `opcontrol` to understand when the task should
@@ -184,16 +268,6 @@ macro_rules! pros_unsafe_err_sig {
 	};
 }
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! dbg_duration_is_u32 {
-	($duration:expr) => {
-		if cfg!(debug_assertions) && $duration.as_millis() > u32::MAX as _ {
-			panic!("cannot use duration where ms > u32::MAX");
-		}
-	};
-}
-
 #[macro_export]
 macro_rules! cstr {
 	($s:expr) => {
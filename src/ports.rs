@@ -2,7 +2,8 @@
 
 use crate::bindings::*;
 use crate::devices::{
-	distance::*, gps::*, imu::*, led::*, motor::*, rotation::*, vision::*, DeviceError, Direction,
+	adi::*, distance::*, gps::*, imu::*, led::*, motor::*, optical::*, rotation::*, serial::*,
+	vision::*, DeviceError, Direction,
 };
 
 use core::num::NonZeroU8;
@@ -67,6 +68,64 @@ impl Port {
 		unsafe { registry_get_plugged_type(self.0.get() - 1).into() }
 	}
 
+	/// This function will return what type of device PROS currently has this
+	/// port **registered** as, which may be different from what is actually
+	/// plugged in. Ports with no device registered are [`DeviceType::None`].
+	#[inline]
+	pub fn bound_type(&self) -> DeviceType {
+		unsafe { registry_get_bound_type(self.0.get() - 1).into() }
+	}
+
+	/// Register this port with PROS as having a device of type `ty`, no matter
+	/// what is currently plugged in. This is needed if a device has not been
+	/// detected yet, such as a sensor which is slow to start up.
+	///
+	/// # Errors
+	/// Returns [`DeviceError::ResourceInUse`] if the port already has a device
+	/// registered, call [`Port::unbind_type()`] first to replace it.
+	pub fn bind_type(&mut self, ty: DeviceType) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			registry_bind_port,
+			err = DeviceError::errno_registry(),
+			self.0.get() - 1,
+			ty.into()
+		)?;
+		Ok(())
+	}
+
+	/// Remove whatever device type PROS has this port registered as.
+	pub fn unbind_type(&mut self) -> Result<(), DeviceError> {
+		pros_unsafe_err!(
+			registry_unbind_port,
+			err = DeviceError::errno_registry(),
+			self.0.get() - 1
+		)?;
+		Ok(())
+	}
+
+	/// Check that the device plugged into this port is of the type `ty`, so
+	/// wiring mistakes can be caught while the robot is being set up rather
+	/// than from an error later on. The port is dropped if the check fails.
+	///
+	/// # Errors
+	/// Returns [`DeviceError::WrongDeviceType`] if [`Port::plugged_type()`]
+	/// is not `ty`.
+	///
+	/// # Examples
+	/// ```
+	/// let motor = devices
+	/// 	.take_port(5)?
+	/// 	.expect_type(DeviceType::Motor)?
+	/// 	.into_motor_default()?;
+	/// ```
+	pub fn expect_type(self, ty: DeviceType) -> Result<Port, DeviceError> {
+		if self.plugged_type() == ty {
+			Ok(self)
+		} else {
+			Err(DeviceError::WrongDeviceType)
+		}
+	}
+
 	/// Convert this port into a new motor object. Semantics are identical to
 	/// [`Motor::new()`].
 	///
@@ -142,6 +201,26 @@ impl Port {
 	pub fn into_distance(self) -> Result<DistanceSensor, DeviceError> {
 		unsafe { DistanceSensor::new(self) }
 	}
+
+	/// Convert this port into a new optical sensor object. Semantics are
+	/// identical to [`OpticalSensor::new()`]
+	///
+	/// # Errors
+	/// Check [`OpticalSensor::new()`] semantics.
+	#[inline]
+	pub fn into_optical(self) -> Result<OpticalSensor, DeviceError> {
+		unsafe { OpticalSensor::new(self) }
+	}
+
+	/// Convert this port into a new generic serial port object. Semantics are
+	/// identical to [`SerialPort::new()`]
+	///
+	/// # Errors
+	/// Check [`SerialPort::new()`] semantics.
+	#[inline]
+	pub fn into_serial(self, baudrate: u32) -> Result<SerialPort, DeviceError> {
+		unsafe { SerialPort::new(self, baudrate) }
+	}
 }
 
 /// What the type of a device is known to be on a V5 port.
@@ -196,6 +275,28 @@ impl From<v5_device_e_t> for DeviceType {
 	}
 }
 
+/// The inverse of `From<v5_device_e_t> for DeviceType`, so converting a
+/// [`DeviceType`] to a `v5_device_e_t` and back again gives the same variant.
+impl From<DeviceType> for v5_device_e_t {
+	fn from(x: DeviceType) -> Self {
+		match x {
+			DeviceType::None => v5_device_e_E_DEVICE_NONE,
+			DeviceType::Motor => v5_device_e_E_DEVICE_MOTOR,
+			DeviceType::Rotation => v5_device_e_E_DEVICE_ROTATION,
+			DeviceType::IMU => v5_device_e_E_DEVICE_IMU,
+			DeviceType::Distance => v5_device_e_E_DEVICE_DISTANCE,
+			DeviceType::Radio => v5_device_e_E_DEVICE_RADIO,
+			DeviceType::Vision => v5_device_e_E_DEVICE_VISION,
+			DeviceType::Adi => v5_device_e_E_DEVICE_ADI,
+			DeviceType::Optical => v5_device_e_E_DEVICE_OPTICAL,
+			DeviceType::GPS => v5_device_e_E_DEVICE_GPS,
+			DeviceType::Serial => v5_device_e_E_DEVICE_GENERIC,
+			DeviceType::Undefined => v5_device_e_E_DEVICE_UNDEFINED,
+			DeviceType::Unknown(x) => x,
+		}
+	}
+}
+
 /// An object of a TriPort on the V5 Brain.
 ///
 /// This is an object which should not be created by hand in most cases. It uses
@@ -259,6 +360,41 @@ impl TriPort {
 	pub fn into_led_strip(self) -> Result<LedStrip, DeviceError> {
 		unsafe { LedStrip::new(self) }
 	}
+
+	/// Convert this TriPort into an LED strip with `len` LEDs. Semantics are
+	/// identical to [`LedStrip::with_length()`].
+	///
+	/// # Errors
+	/// Check [`LedStrip::with_length()`] semantics.
+	#[inline]
+	pub fn into_led_strip_with_length(self, len: usize) -> Result<LedStrip, DeviceError> {
+		unsafe { LedStrip::with_length(self, len) }
+	}
+
+	/// Convert this TriPort and the one after it into a legacy quadrature
+	/// encoder. Semantics are identical to [`AdiEncoder::new()`].
+	///
+	/// # Errors
+	/// Check [`AdiEncoder::new()`] semantics.
+	#[inline]
+	pub fn into_encoder(
+		self,
+		other: TriPort,
+		direction: Direction,
+	) -> Result<AdiEncoder, DeviceError> {
+		unsafe { AdiEncoder::new(self, other, direction) }
+	}
+
+	/// Convert this TriPort and the one after it into a legacy ultrasonic
+	/// rangefinder, with this TriPort as the output. Semantics are identical
+	/// to [`AdiUltrasonic::new()`].
+	///
+	/// # Errors
+	/// Check [`AdiUltrasonic::new()`] semantics.
+	#[inline]
+	pub fn into_ultrasonic(self, echo: TriPort) -> Result<AdiUltrasonic, DeviceError> {
+		unsafe { AdiUltrasonic::new(self, echo) }
+	}
 }
 
 pub(crate) enum TriPortMode {
@@ -376,6 +512,31 @@ pub mod modes {
 			let res = unsafe { ext_adi_port_get_value(self.0.ext_port.get(), self.0.port.get()) };
 			!(res == crate::util::PROS_ERR || res == 0)
 		}
+
+		/// Returns `true` only on the first call after the TriPort goes from
+		/// `LOW` to `HIGH`, so a button or limit switch press is detected once
+		/// no matter how long it is held down for. This is tracked separately
+		/// to [`TriPortDigitalIn::read()`], calling one does not affect the
+		/// other.
+		///
+		/// The TriPorts have built in pull-up resistors which can not be
+		/// configured, so a switch wired between the signal and ground reads
+		/// as `HIGH` when it is open.
+		///
+		/// # Examples
+		/// ```
+		/// loop {
+		/// 	if button.new_press() {
+		/// 		selected = (selected + 1) % AUTONS.len();
+		/// 	}
+		/// 	Task::delay(Duration::from_millis(20));
+		/// }
+		/// ```
+		pub fn new_press(&self) -> bool {
+			let res =
+				unsafe { ext_adi_digital_get_new_press(self.0.ext_port.get(), self.0.port.get()) };
+			!(res == crate::util::PROS_ERR || res == 0)
+		}
 	}
 
 	/// Wrapping of a TriPort, limiting it to being a single digital output.
@@ -1,5 +1,9 @@
 use crate::bindings;
-use crate::rtos::{tasks::Task, time::Duration};
+use crate::rtos::{
+	tasks::Task,
+	time::{Duration, Instant},
+};
+use crate::util::duration_to_ms;
 
 /// Budget future go brrrp
 pub trait Action {
@@ -10,6 +14,47 @@ pub trait Action {
 	fn poll(&mut self) -> Poll<Self::Output>;
 	/// Soonest possible time our action might be complete.
 	fn next(&mut self) -> NextSleep;
+
+	/// Give up on this action if it has not completed within `timeout`. The
+	/// returned action completes with `Some` containing the output of this
+	/// action, or `None` if the timeout elapsed first. The timeout starts from
+	/// when this is called, not when it is first polled.
+	///
+	/// # Examples
+	/// ```
+	/// let mut arm_moved = arm
+	/// 	.move_relative_action(0.5, 100, 0.01)?
+	/// 	.timeout(Duration::from_secs(2));
+	/// action! {
+	/// 	res = arm_moved => match res {
+	/// 		Some(res) => res?,
+	/// 		None => println!("arm did not reach its target in time"),
+	/// 	},
+	/// }
+	/// ```
+	fn timeout(self, timeout: Duration) -> Timeout<Self>
+	where
+		Self: Sized,
+	{
+		Timeout {
+			action: self,
+			deadline: Instant::now() + timeout,
+		}
+	}
+
+	/// Transform the output of this action with `f` once it completes. How
+	/// long to sleep for is left unchanged.
+	///
+	/// # Examples
+	/// ```
+	/// let mut tick = timer.action().map(|_| ControlState::Update);
+	/// ```
+	fn map<U, F: FnMut(Self::Output) -> U>(self, f: F) -> Map<Self, F>
+	where
+		Self: Sized,
+	{
+		Map { action: self, f }
+	}
 }
 
 /// An enum to store whether our action is complete or still waiting.
@@ -38,6 +83,17 @@ pub enum NextSleep {
 	/// The executor should sleep until for this length of time and the poll
 	/// again to see if the action is complete.
 	Timestamp(Duration),
+	/// The executor should sleep until this point in time and then poll again
+	/// to see if the action is complete. Prefer this over
+	/// [`NextSleep::Timestamp`] for periodic actions, as time spent between
+	/// [`Action::next()`] and actually sleeping does not delay the wake up.
+	Deadline(Instant),
+	/// The executor should give up the rest of its time-slice to any other
+	/// tasks which are ready to run, and then poll again straight away.
+	///
+	/// This only lets tasks of the same or a higher priority run, a task which
+	/// always yields will still starve any lower priority tasks.
+	Yield,
 }
 
 impl NextSleep {
@@ -45,12 +101,113 @@ impl NextSleep {
 		match self {
 			NextSleep::Never => (),
 			NextSleep::Notification(time) => {
-				let time = time.map(|x| x.as_millis() as u32).unwrap_or(u32::MAX);
+				let time = time.map(duration_to_ms).unwrap_or(u32::MAX);
 				unsafe {
 					bindings::task_notify_take(true, time);
 				}
 			}
 			NextSleep::Timestamp(time) => Task::delay(time),
+			NextSleep::Deadline(time) => Task::delay_until(time),
+			// Delaying for no time at all still yields to other tasks
+			NextSleep::Yield => Task::delay(Duration::ZERO),
+		}
+	}
+}
+
+/// Returns an action which will complete once `f` returns `true`, calling it
+/// every `period` until then.
+///
+/// # Examples
+/// ```
+/// let mut close = poll_until(
+/// 	|| distance.get_distance().unwrap() < 100,
+/// 	Duration::from_millis(10),
+/// );
+/// action! {
+/// 	_ = close => drive.stop()?,
+/// }
+/// ```
+pub fn poll_until<F: FnMut() -> bool>(f: F, period: Duration) -> impl Action<Output = ()> {
+	struct PollUntil<F> {
+		f: F,
+		period: Duration,
+	}
+
+	impl<F: FnMut() -> bool> Action for PollUntil<F> {
+		type Output = ();
+
+		fn poll(&mut self) -> Poll<Self::Output> {
+			if (self.f)() {
+				Poll::Complete(())
+			} else {
+				Poll::Waiting
+			}
+		}
+
+		fn next(&mut self) -> NextSleep {
+			NextSleep::Timestamp(self.period)
+		}
+	}
+
+	PollUntil { f, period }
+}
+
+/// An action which transforms the output of another action, created by
+/// [`Action::map()`].
+pub struct Map<A, F> {
+	action: A,
+	f: F,
+}
+
+impl<A: Action, U, F: FnMut(A::Output) -> U> Action for Map<A, F> {
+	type Output = U;
+
+	fn poll(&mut self) -> Poll<Self::Output> {
+		match self.action.poll() {
+			Poll::Complete(out) => Poll::Complete((self.f)(out)),
+			Poll::Waiting => Poll::Waiting,
+		}
+	}
+
+	fn next(&mut self) -> NextSleep {
+		self.action.next()
+	}
+}
+
+/// An action which completes with `None` if the inner action does not complete
+/// before a deadline, created by [`Action::timeout()`].
+pub struct Timeout<A> {
+	action: A,
+	deadline: Instant,
+}
+
+impl<A: Action> Timeout<A> {
+	fn time_left(&self) -> Duration {
+		self.deadline.saturating_duration_since(Instant::now())
+	}
+}
+
+impl<A: Action> Action for Timeout<A> {
+	type Output = Option<A::Output>;
+
+	fn poll(&mut self) -> Poll<Self::Output> {
+		match self.action.poll() {
+			Poll::Complete(out) => Poll::Complete(Some(out)),
+			Poll::Waiting if Instant::now() >= self.deadline => Poll::Complete(None),
+			Poll::Waiting => Poll::Waiting,
+		}
+	}
+
+	// Whichever of the inner action or our deadline is sooner
+	fn next(&mut self) -> NextSleep {
+		let left = self.time_left();
+		match self.action.next() {
+			NextSleep::Never => NextSleep::Deadline(self.deadline),
+			NextSleep::Notification(None) => NextSleep::Notification(Some(left)),
+			NextSleep::Notification(Some(t)) => NextSleep::Notification(Some(t.min(left))),
+			NextSleep::Timestamp(t) => NextSleep::Timestamp(t.min(left)),
+			NextSleep::Deadline(t) => NextSleep::Deadline(t.min(self.deadline)),
+			NextSleep::Yield => NextSleep::Yield,
 		}
 	}
 }
@@ -4,20 +4,27 @@
 pub mod action;
 pub mod tasks;
 pub mod time;
+pub mod watchdog;
 
 use crate::bindings;
 use crate::devices::DeviceError;
+use crate::util::duration_to_ms;
 
 use core::{
 	cell::UnsafeCell,
 	mem::MaybeUninit,
 	ops::{Deref, DerefMut},
-	sync::atomic::{self, AtomicBool, Ordering},
+	ptr,
+	sync::atomic::{AtomicPtr, AtomicU8, Ordering},
 	time::Duration,
 };
 
 struct MutexInner {
 	ptr: *mut libc::c_void,
+	/// The task which currently holds this mutex, only tracked in debug builds
+	/// to catch a task locking a mutex it already holds.
+	#[cfg(debug_assertions)]
+	owner: AtomicPtr<libc::c_void>,
 }
 
 impl MutexInner {
@@ -30,16 +37,50 @@ impl MutexInner {
 		if ptr.is_null() {
 			Err(DeviceError::errno_generic())
 		} else {
-			Ok(MutexInner { ptr })
+			Ok(MutexInner {
+				ptr,
+				#[cfg(debug_assertions)]
+				owner: AtomicPtr::new(ptr::null_mut()),
+			})
 		}
 	}
 
 	pub fn take(&self, timeout: Duration) -> bool {
-		dbg_duration_is_u32!(timeout);
-		unsafe { bindings::mutex_take(self.ptr, timeout.as_millis() as u32) }
+		// Only the owning task ever stores itself as the owner, so if we see
+		// ourselves here we really are trying to take the mutex twice. A zero
+		// timeout can't deadlock, it will just fail to take the mutex.
+		#[cfg(debug_assertions)]
+		let current = unsafe { bindings::task_get_current() };
+		#[cfg(debug_assertions)]
+		debug_assert!(
+			timeout.is_zero() || self.owner.load(Ordering::Relaxed) != current,
+			"attempted to lock a mutex which is already held by the current task, this would \
+			 deadlock"
+		);
+
+		let taken = unsafe { bindings::mutex_take(self.ptr, duration_to_ms(timeout)) };
+		#[cfg(debug_assertions)]
+		if taken {
+			self.owner.store(current, Ordering::Relaxed);
+		}
+		taken
+	}
+
+	/// The name of the task which currently holds this mutex, for reporting a
+	/// likely deadlock.
+	#[cfg(debug_assertions)]
+	pub fn owner_name(&self) -> alloc::string::String {
+		let owner = self.owner.load(Ordering::Relaxed);
+		if owner.is_null() {
+			alloc::string::String::from("<unknown>")
+		} else {
+			crate::util::cstring_from(unsafe { bindings::task_get_name(owner) })
+		}
 	}
 
 	pub fn give(&self) -> bool {
+		#[cfg(debug_assertions)]
+		self.owner.store(ptr::null_mut(), Ordering::Relaxed);
 		unsafe { bindings::mutex_give(self.ptr) }
 	}
 }
@@ -53,6 +94,8 @@ impl Drop for MutexInner {
 /// A mutual exclusion primitive useful for protecting shared date.
 pub struct Mutex<T: ?Sized> {
 	mutex: MutexInner,
+	/// How long [`Mutex::lock()`] waits before assuming there is a deadlock.
+	timeout: Duration,
 	data: UnsafeCell<T>,
 }
 
@@ -71,8 +114,25 @@ impl<T> Mutex<T> {
 	/// let mutex = Mutex::new(0);
 	/// ```
 	pub fn new(t: T) -> Mutex<T> {
+		Self::with_timeout(t, time::INF_TIMEOUT)
+	}
+
+	/// Creates a new mutex in an unlocked state, where [`Mutex::lock()`] will
+	/// give up waiting for the lock after `timeout`. This is useful for
+	/// catching a task which holds onto the lock for far too long.
+	///
+	/// # Panics
+	/// This function will panic if it is unable to create the mutex.
+	///
+	/// # Examples
+	/// ```
+	/// use pros::rtos::Mutex;
+	/// let pose = Mutex::with_timeout(Pose::default(), Duration::from_millis(100));
+	/// ```
+	pub fn with_timeout(t: T, timeout: Duration) -> Mutex<T> {
 		Mutex {
 			mutex: MutexInner::new(),
+			timeout,
 			data: UnsafeCell::new(t),
 		}
 	}
@@ -82,10 +142,29 @@ impl<T: ?Sized> Mutex<T> {
 	/// Acquires a mutex block the current task until it able to do so.
 	///
 	/// The semantics of this function are the exact same as
-	/// [`Mutex::lock_timeout`] however the timeout if infinite.
+	/// [`Mutex::lock_timeout`] however the timeout is the one given to
+	/// [`Mutex::with_timeout()`], which is infinite for [`Mutex::new()`].
+	///
+	/// # Panics
+	/// In debug builds this will panic if the timeout is reached, naming the
+	/// task which is holding the lock. In release builds it will carry on
+	/// waiting forever.
 	pub fn lock(&self) -> MutexGuard<'_, T> {
+		if let Some(guard) = self.lock_timeout(self.timeout) {
+			return guard;
+		}
+
+		#[cfg(debug_assertions)]
+		panic!(
+			"timed out after {:?} waiting to lock a mutex held by task '{}', this is likely a \
+			 deadlock",
+			self.timeout,
+			self.mutex.owner_name()
+		);
+
 		// A timeout of u32::MAX is the same value as `TIMEOUT_MAX` in PROS and
 		// will block indefinitely, this is why `None` should be unreachable
+		#[cfg(not(debug_assertions))]
 		match self.lock_timeout(time::INF_TIMEOUT) {
 			Some(guard) => guard,
 			None => unreachable!(),
@@ -103,7 +182,8 @@ impl<T: ?Sized> Mutex<T> {
 	///
 	/// If you call `lock` on this mutex from the same task the behaviour is
 	/// undefined. This function may return, there may be a panic of a deadlock.
-	/// Be cautious.
+	/// Be cautious. In debug builds this is detected and will panic, unless the
+	/// timeout is zero.
 	///
 	/// # Errors
 	/// This function will return an option if the Mutex was unable to be
@@ -116,6 +196,23 @@ impl<T: ?Sized> Mutex<T> {
 		}
 	}
 
+	/// Attempts to acquire the mutex without blocking the current task.
+	///
+	/// This is the same as calling [`Mutex::lock_timeout`] with a zero
+	/// timeout, the mutex is only taken if it is free at the moment this is
+	/// called. If it is held by another task `None` is returned immediately.
+	///
+	/// # Examples
+	/// ```
+	/// // Skip this update rather than stall the control loop
+	/// if let Some(mut pose) = shared_pose.try_lock() {
+	/// 	*pose = odometry.pose();
+	/// }
+	/// ```
+	pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
+		self.lock_timeout(Duration::ZERO)
+	}
+
 	/// Consumes this mutex, returning the underlying data.
 	pub fn into_inner(self) -> T
 	where
@@ -181,6 +278,191 @@ impl<T: ?Sized> Drop for MutexGuard<'_, T> {
 	}
 }
 
+/// A reader-writer lock, allowing many tasks to read the protected data at
+/// once, or a single task to write to it.
+///
+/// This is built from a counting [`Semaphore`] which hands out a permit to each
+/// reader, and a gate mutex. Readers pass through the gate to take a permit,
+/// while a writer holds the gate for as long as it is writing and takes every
+/// permit, waiting for the current readers to finish.
+///
+/// Because a waiting writer holds the gate, tasks which try to read after a
+/// writer has started waiting will queue behind it, so readers can never
+/// starve a writer. The opposite is possible though, a constant stream of
+/// writers will starve any readers. At most [`RwLock::MAX_READERS`] tasks may
+/// read at once, any more will block until another reader has finished.
+///
+/// # Examples
+/// ```
+/// let pose = RwLock::new(Pose::default());
+/// // From the odometry task
+/// *pose.write() = odometry.update();
+/// // From any number of other tasks
+/// let current = *pose.read();
+/// ```
+pub struct RwLock<T: ?Sized> {
+	gate: MutexInner,
+	readers: Semaphore,
+	data: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for RwLock<T> {}
+unsafe impl<T: ?Sized + Send + Sync> Sync for RwLock<T> {}
+
+impl<T> RwLock<T> {
+	/// Creates a new reader-writer lock in an unlocked state ready to use.
+	///
+	/// # Panics
+	/// This function will panic if it is unable to create the underlying
+	/// mutex or semaphore.
+	pub fn new(t: T) -> RwLock<T> {
+		RwLock {
+			gate: MutexInner::new(),
+			readers: Semaphore::new(Self::MAX_READERS, Self::MAX_READERS),
+			data: UnsafeCell::new(t),
+		}
+	}
+}
+
+impl<T: ?Sized> RwLock<T> {
+	/// The maximum amount of tasks which can hold a read lock at once.
+	pub const MAX_READERS: u32 = 16;
+
+	/// Acquire shared read access, blocking the current task until there are
+	/// no writers holding or waiting for the lock.
+	pub fn read(&self) -> RwLockReadGuard<'_, T> {
+		let taken = self.gate.take(time::INF_TIMEOUT);
+		debug_assert!(taken);
+		let permit = self.readers.wait();
+		debug_assert!(permit);
+		self.gate.give();
+		RwLockReadGuard { lock: self }
+	}
+
+	/// Attempt to acquire shared read access without blocking, returns `None`
+	/// if a writer is holding or waiting for the lock.
+	pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T>> {
+		if !self.gate.take(Duration::ZERO) {
+			return None;
+		}
+		let permit = self.readers.poll();
+		self.gate.give();
+		permit.then_some(RwLockReadGuard { lock: self })
+	}
+
+	/// Acquire exclusive write access, blocking the current task until all
+	/// other readers and writers have released the lock.
+	pub fn write(&self) -> RwLockWriteGuard<'_, T> {
+		let taken = self.gate.take(time::INF_TIMEOUT);
+		debug_assert!(taken);
+		for _ in 0..Self::MAX_READERS {
+			let permit = self.readers.wait();
+			debug_assert!(permit);
+		}
+		RwLockWriteGuard { lock: self }
+	}
+
+	/// Attempt to acquire exclusive write access without blocking, returns
+	/// `None` if any other task holds the lock.
+	pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T>> {
+		if !self.gate.take(Duration::ZERO) {
+			return None;
+		}
+		for taken in 0..Self::MAX_READERS {
+			if !self.readers.poll() {
+				// Hand back the permits we managed to take before giving up
+				for _ in 0..taken {
+					let _ = self.readers.post();
+				}
+				self.gate.give();
+				return None;
+			}
+		}
+		Some(RwLockWriteGuard { lock: self })
+	}
+
+	/// Consumes this lock, returning the underlying data.
+	pub fn into_inner(self) -> T
+	where
+		T: Sized,
+	{
+		self.data.into_inner()
+	}
+
+	/// Returns a mutable reference to the underlying data.
+	///
+	/// Since this function calls `RwLock` mutably, no locking actually needs
+	/// to take place, we are the only one with it.
+	pub fn get_mut(&mut self) -> &mut T {
+		self.data.get_mut()
+	}
+}
+
+impl<T: ?Sized + Default> Default for RwLock<T> {
+	/// Creates a new unlocked `RwLock<T>` with the `Default` value for T.
+	fn default() -> RwLock<T> {
+		RwLock::new(Default::default())
+	}
+}
+
+/// A RAII guard for shared read access to an [`RwLock`]. When this structure
+/// is dropped the read lock will be released.
+pub struct RwLockReadGuard<'a, T: ?Sized + 'a> {
+	lock: &'a RwLock<T>,
+}
+
+impl<T: ?Sized> !Send for RwLockReadGuard<'_, T> {}
+unsafe impl<T: ?Sized + Sync> Sync for RwLockReadGuard<'_, T> {}
+
+impl<T: ?Sized> Deref for RwLockReadGuard<'_, T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		unsafe { &*self.lock.data.get() }
+	}
+}
+
+impl<T: ?Sized> Drop for RwLockReadGuard<'_, T> {
+	fn drop(&mut self) {
+		let result = self.lock.readers.post();
+		debug_assert!(result.is_ok());
+	}
+}
+
+/// A RAII guard for exclusive write access to an [`RwLock`]. When this
+/// structure is dropped the write lock will be released.
+pub struct RwLockWriteGuard<'a, T: ?Sized + 'a> {
+	lock: &'a RwLock<T>,
+}
+
+impl<T: ?Sized> !Send for RwLockWriteGuard<'_, T> {}
+unsafe impl<T: ?Sized + Sync> Sync for RwLockWriteGuard<'_, T> {}
+
+impl<T: ?Sized> Deref for RwLockWriteGuard<'_, T> {
+	type Target = T;
+
+	fn deref(&self) -> &T {
+		unsafe { &*self.lock.data.get() }
+	}
+}
+
+impl<T: ?Sized> DerefMut for RwLockWriteGuard<'_, T> {
+	fn deref_mut(&mut self) -> &mut T {
+		unsafe { &mut *self.lock.data.get() }
+	}
+}
+
+impl<T: ?Sized> Drop for RwLockWriteGuard<'_, T> {
+	fn drop(&mut self) {
+		for _ in 0..RwLock::<T>::MAX_READERS {
+			let result = self.lock.readers.post();
+			debug_assert!(result.is_ok());
+		}
+		let result = self.lock.gate.give();
+		debug_assert!(result);
+	}
+}
+
 pub struct Semaphore {
 	ptr: *mut libc::c_void,
 }
@@ -202,6 +484,8 @@ impl Semaphore {
 		}
 	}
 
+	/// Wait for the semaphore to become available, decrementing its count.
+	/// This blocks the current task indefinitely until it is able to do so.
 	pub fn wait(&self) -> bool {
 		self.wait_timeout(time::INF_TIMEOUT)
 	}
@@ -211,15 +495,16 @@ impl Semaphore {
 	}
 
 	pub fn wait_timeout(&self, timeout: Duration) -> bool {
-		dbg_duration_is_u32!(timeout);
-		unsafe { bindings::sem_wait(self.ptr, timeout.as_millis() as u32) }
+		unsafe { bindings::sem_wait(self.ptr, duration_to_ms(timeout)) }
 	}
 
+	/// Increment the count of this semaphore, waking a task which is waiting
+	/// on it if there is one.
 	pub fn post(&self) -> Result<(), DeviceError> {
-		if unsafe { bindings::mutex_give(self.ptr) } {
-			Err(DeviceError::errno_generic())
-		} else {
+		if unsafe { bindings::sem_post(self.ptr) } {
 			Ok(())
+		} else {
+			Err(DeviceError::errno_generic())
 		}
 	}
 
@@ -235,44 +520,64 @@ impl Drop for Semaphore {
 }
 
 pub struct OnceCell<T> {
-	has_init: AtomicBool,
+	state: AtomicU8,
 	item: UnsafeCell<MaybeUninit<T>>,
 }
 
 unsafe impl<T: Send> Sync for OnceCell<T> {}
 
 impl<T> OnceCell<T> {
+	const UNINIT: u8 = 0;
+	const INITIALIZING: u8 = 1;
+	const COMPLETE: u8 = 2;
+
 	pub const fn new() -> Self {
 		Self {
-			has_init: AtomicBool::new(false),
+			state: AtomicU8::new(Self::UNINIT),
 			item: UnsafeCell::new(MaybeUninit::uninit()),
 		}
 	}
 
-	/// This function will only ever be called once
+	/// This function will only ever be called once. If multiple tasks race to
+	/// call this only one of them will run `f`, the others will wait until it
+	/// has finished before returning.
+	///
+	/// If `f` panics the cell is left initialising forever, and any task
+	/// waiting on it will never return.
 	pub fn call_once<F: FnOnce() -> T>(&self, f: F) {
-		if self.has_init.load(Ordering::Acquire) {
-			return;
-		}
-		unsafe {
-			(*self.item.get()).write(f());
+		match self.state.compare_exchange(
+			Self::UNINIT,
+			Self::INITIALIZING,
+			Ordering::Acquire,
+			Ordering::Acquire,
+		) {
+			Ok(_) => {
+				unsafe {
+					(*self.item.get()).write(f());
+				}
+				self.state.store(Self::COMPLETE, Ordering::Release);
+			}
+			// Someone else is, or has already, set the value
+			Err(_) => {
+				self.wait();
+			}
 		}
-		atomic::fence(Ordering::Release);
-		self.has_init.store(true, Ordering::Relaxed);
 	}
 
 	/// Check to see if the [`OnceCell::call_once()`] function has set the inner
 	/// value.
 	pub fn is_completed(&self) -> bool {
-		self.has_init.load(Ordering::Relaxed)
+		self.state.load(Ordering::Acquire) == Self::COMPLETE
 	}
 
-	/// Wait for self.item to be set to something with a spinlock.
+	/// Wait for self.item to be set to something, sleeping between checks so
+	/// the task running the initialiser is able to make progress.
 	pub fn wait(&self) -> &T {
-		while !self.has_init.load(Ordering::Relaxed) {
-			core::hint::spin_loop();
+		while !self.is_completed() {
+			// A zero delay only yields to tasks of the same priority, so block
+			// for a tick to let a lower priority initialiser run
+			tasks::Task::delay(Duration::from_millis(1));
 		}
-		atomic::fence(Ordering::Acquire);
 		unsafe { (*self.item.get()).assume_init_ref() }
 	}
 }
@@ -1,17 +1,19 @@
 use crate::bindings;
+use crate::devices::controller::CompetitionMode;
 use crate::rtos::{
 	action::{Action, NextSleep, Poll},
+	time::{Duration, Instant},
 	Mutex,
 };
-use crate::util::to_cstring;
+use crate::util::{cstring_from, duration_to_ms, to_cstring};
 
 use alloc::{boxed::Box, string::String, sync::Arc};
-use core::time::Duration;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 #[derive(Clone)]
 pub struct Task {
 	repr: *mut core::ffi::c_void,
-	name: Option<*const libc::c_char>,
+	name: Option<String>,
 }
 
 impl Task {
@@ -34,6 +36,26 @@ impl Task {
 		}
 	}
 
+	/// Find a task by the name it was given when it was spawned, returns `None`
+	/// if there is no task with this name. If more than one task shares the
+	/// name any one of them may be returned.
+	///
+	/// # Examples
+	/// ```
+	/// if let Some(task) = Task::by_name("intake") {
+	/// 	task.suspend();
+	/// }
+	/// ```
+	pub fn by_name(name: &str) -> Option<Task> {
+		let name = to_cstring(String::from(name));
+		let repr = unsafe { bindings::task_get_by_name(name.as_ptr() as _) };
+		if repr.is_null() {
+			None
+		} else {
+			Some(Task { repr, name: None })
+		}
+	}
+
 	/// Delay the current task for at least however many milliseconds that is
 	/// stored in the duration.
 	///
@@ -45,21 +67,53 @@ impl Task {
 	/// delay(Duration::from_seconds(4));
 	/// ```
 	pub fn delay(dur: Duration) {
-		unsafe { bindings::task_delay(dur.as_millis() as u32) }
+		unsafe { bindings::task_delay(duration_to_ms(dur)) }
+	}
+
+	/// Delay the current task until at least the time stored in `deadline`,
+	/// returning straight away if it has already passed.
+	///
+	/// Unlike [`Task::delay()`] the wake time is absolute, so being preempted
+	/// between working out the deadline and calling this does not push it any
+	/// later. Prefer this for anything periodic.
+	///
+	/// # Examples
+	/// ```
+	/// let mut next = Instant::now();
+	/// loop {
+	/// 	next += Duration::from_millis(20);
+	/// 	// control loop
+	/// 	Task::delay_until(next);
+	/// }
+	/// ```
+	pub fn delay_until(deadline: Instant) {
+		let mut now = unsafe { bindings::millis() };
+		// Round up so that we never wake before the deadline
+		let target = deadline.as_micros().div_ceil(1000);
+		if let Some(delta) = target.checked_sub(now as u64) {
+			let delta = delta.min(u32::MAX as u64) as u32;
+			unsafe { bindings::task_delay_until(&mut now, delta) }
+		}
 	}
 
 	/// Get the name of this thread, it is possible that this thread does not
 	/// have name. In this case the string returned will be of zero length.
-	pub fn name<'a>(&mut self) -> &'a str {
-		if self.name.is_none() {
-			self.name = Some(unsafe { bindings::task_get_name(self.repr) as _ });
-		}
+	///
+	/// The name is copied out of PROS the first time this is called and cached
+	/// in this handle, so the returned string remains valid for as long as the
+	/// handle is borrowed, even if the task itself has since exited.
+	pub fn name(&mut self) -> &str {
+		let repr = self.repr;
+		self.name
+			.get_or_insert_with(|| cstring_from(unsafe { bindings::task_get_name(repr) }))
+	}
 
-		let name = self.name.unwrap();
-		unsafe {
-			let slice = core::slice::from_raw_parts(name, libc::strlen(name)) as &[u8];
-			core::str::from_utf8(slice).unwrap()
-		}
+	/// Get an owned copy of the name of this thread. Unlike [`Task::name()`]
+	/// this does not need a mutable handle and always reads the current name
+	/// from PROS, which makes it convenient for collecting names to store or
+	/// print later.
+	pub fn name_owned(&self) -> String {
+		cstring_from(unsafe { bindings::task_get_name(self.repr) })
 	}
 
 	/// If this task was previously suspended before it will now considered
@@ -124,21 +178,49 @@ impl Task {
 		}
 	}
 
-	pub fn notify(&self) {
+	/// Send a notification to this task, incrementing its notification value.
+	/// If the task is blocked in [`notify_take()`] it will be woken.
+	///
+	/// PROS always returns `1` from this, it is passed through as is.
+	pub fn notify(&self) -> u32 {
 		// Make sure that we aren't trying to notify ourselves, that wouldn't make any
 		// sense
 		debug_assert!(self.repr != Task::current().repr);
-		unsafe { bindings::task_notify(self.repr) };
+		unsafe { bindings::task_notify(self.repr) }
 	}
 
+	/// Send a notification to this task, updating its notification value with
+	/// `value` according to `action`. If the task is blocked in
+	/// [`notify_take()`] it will be woken.
+	///
+	/// Returns whether the notification value was updated, followed by the
+	/// notification value from before it was updated. The value is only left
+	/// unchanged when using [`NotifyAction::NoOverwrite`] while the task
+	/// already has a notification pending.
+	///
+	/// # Examples
+	/// ```
+	/// // Set bit 2 of the task's notification value
+	/// task.notify_ext(1 << 2, NotifyAction::Bits);
+	/// ```
+	pub fn notify_ext(&self, value: u32, action: NotifyAction) -> (bool, u32) {
+		let mut prev = 0;
+		let res = unsafe { bindings::task_notify_ext(self.repr, value, action.into(), &mut prev) };
+		(res != 0, prev)
+	}
+
+	/// Wait for a notification to be sent to the current task, refer to
+	/// [`notify_take()`]. This must only be called on the handle of the
+	/// current task.
 	pub fn notify_take(&self, clear: bool, timeout: Duration) -> u32 {
 		debug_assert!(self.repr == Task::current().repr);
-		dbg_duration_is_u32!(timeout);
-		unsafe { bindings::task_notify_take(clear, timeout.as_millis() as u32) }
+		notify_take(clear, timeout)
 	}
 
-	pub fn notify_clear(&self) {
-		unsafe { bindings::task_notify_clear(self.repr) };
+	/// Clear any pending notification for this task. Returns `true` if there
+	/// was a notification pending.
+	pub fn notify_clear(&self) -> bool {
+		unsafe { bindings::task_notify_clear(self.repr) }
 	}
 
 	pub fn get_state(&self) -> TaskState {
@@ -146,9 +228,70 @@ impl Task {
 	}
 }
 
+// SAFETY: `repr` is an opaque handle owned by the RTOS, every operation on it
+// goes through the PROS API which is safe to call from any task. The cached
+// name is an owned copy and never points back into the task's memory.
 unsafe impl Send for Task {}
 unsafe impl Sync for Task {}
 
+/// Get the number of tasks which are currently being managed by the RTOS,
+/// including any created by PROS itself.
+pub fn task_count() -> u32 {
+	unsafe { bindings::task_get_count() }
+}
+
+/// How the notification value of a task is updated by [`Task::notify_ext()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyAction {
+	/// The notification value is not changed.
+	None,
+	/// The bits of the value are set in the notification value.
+	Bits,
+	/// The notification value is incremented, the value is ignored.
+	Increment,
+	/// The notification value is overwritten with the value.
+	Overwrite,
+	/// The notification value is overwritten with the value, but only if the
+	/// task does not already have a notification pending.
+	NoOverwrite,
+}
+
+impl From<NotifyAction> for bindings::notify_action_e_t {
+	fn from(f: NotifyAction) -> Self {
+		use bindings::*;
+		match f {
+			NotifyAction::None => notify_action_e_t_E_NOTIFY_ACTION_NONE,
+			NotifyAction::Bits => notify_action_e_t_E_NOTIFY_ACTION_BITS,
+			NotifyAction::Increment => notify_action_e_t_E_NOTIFY_ACTION_INCR,
+			NotifyAction::Overwrite => notify_action_e_t_E_NOTIFY_ACTION_OWRITE,
+			NotifyAction::NoOverwrite => notify_action_e_t_E_NOTIFY_ACTION_NO_OWRITE,
+		}
+	}
+}
+
+/// Block the current task until it receives a notification or `timeout` has
+/// elapsed.
+///
+/// Returns the notification value of the task from before it was decremented
+/// or cleared, so `0` means that the timeout elapsed without any notification.
+/// When `clear` is `true` the notification value is reset to zero on exit,
+/// making the task wake once for any number of notifications. Otherwise it is
+/// decremented, so each notification is taken one at a time like a counting
+/// semaphore.
+///
+/// # Examples
+/// ```
+/// let worker = tasks::spawn(|| loop {
+/// 	if tasks::notify_take(true, Duration::from_millis(100)) > 0 {
+/// 		// new sensor data is ready
+/// 	}
+/// });
+/// worker.notify();
+/// ```
+pub fn notify_take(clear: bool, timeout: Duration) -> u32 {
+	unsafe { bindings::task_notify_take(clear, duration_to_ms(timeout)) }
+}
+
 /// What state the task is in currently as seen by FreeRTOS.
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum TaskState {
@@ -259,6 +402,26 @@ impl TaskBuilder {
 			}
 		}
 	}
+
+	/// Spawn a new task like [`TaskBuilder::spawn()`], the value returned by
+	/// `f` can be collected with [`JoinHandle::join()`].
+	pub fn spawn_with_result<T, F>(self, f: F) -> Result<JoinHandle<T>, ()>
+	where
+		T: Send + 'static,
+		F: FnOnce() -> T + Send + 'static,
+	{
+		// the result is written before the task function returns, so it is
+		// always there by the time the task can be joined
+		let result = Arc::new(Mutex::new(None));
+		let task = {
+			let result = result.clone();
+			self.spawn(move || {
+				let r = f();
+				*result.lock() = Some(r);
+			})?
+		};
+		Ok(JoinHandle { task, result })
+	}
 }
 
 /// Spawn a new task with the default stack size and priority.
@@ -282,10 +445,186 @@ pub fn spawn<F: FnOnce() + Send + 'static>(f: F) -> Task {
 	TaskBuilder::new().spawn(f).expect("failed to spawn task")
 }
 
+/// Spawn a new task with the default stack size and priority, the value
+/// returned by `f` can be collected with [`JoinHandle::join()`].
+///
+/// # Panics
+/// Panics if the task cannot be spawned, refer to [`TaskBuilder::spawn()`]'s
+/// error return type for more possible reasons.
+///
+/// # Examples
+/// ```
+/// let heading = spawn_with_result(move || {
+/// 	imu.calibrate_blocking(Duration::from_secs(3)).unwrap();
+/// 	imu.get_heading().unwrap()
+/// });
+/// // do some other setup while the IMU is calibrating
+/// println!("starting heading: {}", heading.join());
+/// ```
+pub fn spawn_with_result<T, F>(f: F) -> JoinHandle<T>
+where
+	T: Send + 'static,
+	F: FnOnce() -> T + Send + 'static,
+{
+	TaskBuilder::new()
+		.spawn_with_result(f)
+		.expect("failed to spawn task")
+}
+
+/// Block the current task until every task in `tasks` has completed and
+/// exited, see [`Task::join()`].
+pub fn join_all(tasks: &[Task]) {
+	for task in tasks {
+		task.join();
+	}
+}
+
+/// An owned handle to a task which will return a value once it has completed,
+/// created by [`spawn_with_result()`] or [`TaskBuilder::spawn_with_result()`].
+pub struct JoinHandle<T> {
+	task: Task,
+	result: Arc<Mutex<Option<T>>>,
+}
+
+impl<T> JoinHandle<T> {
+	/// Get the task this handle is for.
+	#[inline]
+	pub fn task(&self) -> &Task {
+		&self.task
+	}
+
+	/// Block the current task until the task has completed and exited, then
+	/// return the value it produced.
+	///
+	/// # Panics
+	/// Panics if the task was deleted before it could return a value.
+	pub fn join(self) -> T {
+		self.task.join();
+		self.result
+			.lock()
+			.take()
+			.expect("task was deleted before it returned a value")
+	}
+}
+
+/// How often to check a [`CancelToken`] when waiting for it to be cancelled.
+const CANCEL_POLL_PERIOD: Duration = Duration::from_millis(10);
+
+/// A flag shared between tasks which can be used to ask a task to stop.
+///
+/// Unlike [`Task::delete()`] cancellation is cooperative, the task checks the
+/// token itself and returns when it is cancelled, so everything it owns is
+/// dropped as normal. Cloning the token gives another reference to the same
+/// flag.
+///
+/// # Examples
+/// ```
+/// let token = CancelToken::new();
+/// let task = {
+/// 	let token = token.clone();
+/// 	spawn(move || {
+/// 		let mut timer = Interval::new(Duration::from_millis(10));
+/// 		loop {
+/// 			// background work
+/// 			action! {
+/// 				_ = token.cancelled() => break,
+/// 				_ = timer.action() => continue
+/// 			}
+/// 		}
+/// 	})
+/// };
+/// // later on
+/// token.cancel();
+/// task.join();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+	/// Create a new token which has not been cancelled.
+	pub fn new() -> Self {
+		CancelToken(Arc::new(AtomicBool::new(false)))
+	}
+
+	/// Cancel this token and every clone of it. This does not wait for the
+	/// tasks using the token to stop, join them to do so.
+	#[inline]
+	pub fn cancel(&self) {
+		self.0.store(true, Ordering::Release);
+	}
+
+	/// Returns `true` if this token has been cancelled.
+	#[inline]
+	pub fn is_cancelled(&self) -> bool {
+		self.0.load(Ordering::Acquire)
+	}
+
+	/// Returns an action which will complete once this token is cancelled.
+	pub fn cancelled(&self) -> impl Action<Output = ()> + '_ {
+		struct CancelledAction<'a>(&'a CancelToken);
+
+		impl<'a> Action for CancelledAction<'a> {
+			type Output = ();
+
+			fn poll(&mut self) -> Poll<Self::Output> {
+				if self.0.is_cancelled() {
+					Poll::Complete(())
+				} else {
+					Poll::Waiting
+				}
+			}
+
+			fn next(&mut self) -> NextSleep {
+				NextSleep::Timestamp(CANCEL_POLL_PERIOD)
+			}
+		}
+
+		CancelledAction(self)
+	}
+}
+
+/// Information about the phase of the match which the robot is currently in,
+/// returned by [`CompetitionState::match_info()`]. A new phase starts each
+/// time one of the competition functions of [`Robot`][crate::Robot] is called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchInfo {
+	phase_start: Instant,
+	mode: CompetitionMode,
+}
+
+impl MatchInfo {
+	/// Get when the current phase started. Before the first phase this is the
+	/// start of the program.
+	pub fn phase_start(&self) -> Instant {
+		self.phase_start
+	}
+
+	/// Get how long it has been since the current phase started.
+	///
+	/// # Examples
+	/// ```
+	/// // Driver control is 1 minute 45 seconds long
+	/// let endgame = Duration::from_secs(105 - 15);
+	/// if state.match_info().phase_elapsed() >= endgame {
+	/// 	wings.extend();
+	/// }
+	/// ```
+	pub fn phase_elapsed(&self) -> Duration {
+		self.phase_start.elapsed()
+	}
+
+	/// Get the competition status of the V5 Brain from when the current phase
+	/// started.
+	pub fn mode(&self) -> CompetitionMode {
+		self.mode
+	}
+}
+
 #[derive(Default)]
 struct CompetitionStateInner {
 	opcontrol_task: Option<Task>,
 	autonomous_task: Option<Task>,
+	phase: Option<MatchInfo>,
 }
 
 pub struct CompetitionState(Arc<Mutex<CompetitionStateInner>>);
@@ -308,6 +647,24 @@ impl CompetitionState {
 		s.autonomous_task = Some(task);
 	}
 
+	#[doc(hidden)]
+	pub fn start_phase(&self) {
+		let mut s = self.0.lock();
+		s.phase = Some(MatchInfo {
+			phase_start: Instant::now(),
+			mode: CompetitionMode::get_status(),
+		});
+	}
+
+	/// Get information about the phase of the match which the robot is
+	/// currently in, such as how long ago it started.
+	pub fn match_info(&self) -> MatchInfo {
+		self.0.lock().phase.unwrap_or_else(|| MatchInfo {
+			phase_start: Instant::from_micros(0),
+			mode: CompetitionMode::get_status(),
+		})
+	}
+
 	/// Check to see if the specific competition task has been completed or
 	/// killed by the Field Management System.
 	pub fn task_done(&'_ self, task: CompetitionTask) -> impl Action + '_ {
@@ -8,7 +8,11 @@ use core::ops::{Add, AddAssign, Sub};
 pub use core::time::Duration;
 
 /// A duration which is representative of a value which will never timeout.
-pub(crate) const INF_TIMEOUT: Duration = Duration::from_millis(u32::MAX as _);
+///
+/// This is exactly `u32::MAX` milliseconds, which is what PROS uses as
+/// `TIMEOUT_MAX`. Any longer duration is also treated as never timing out, as
+/// [`duration_to_ms()`][crate::util::duration_to_ms] saturates at this value.
+pub const INF_TIMEOUT: Duration = Duration::from_millis(u32::MAX as u64);
 
 /// A sample of a monotonically nondecreasing clock running from the start of
 /// program execution. Used to represent a point in time of the programs
@@ -16,7 +20,7 @@ pub(crate) const INF_TIMEOUT: Duration = Duration::from_millis(u32::MAX as _);
 ///
 /// This type is similar to the on in `std` however it less strict and is more
 /// aimed for marking times throughout the programs execution.
-#[derive(Clone, Copy, PartialOrd, Ord, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq)]
 pub struct Instant(u64);
 
 impl Instant {
@@ -88,6 +92,7 @@ impl Instant {
 		self.0 % 1000000
 	}
 
+	/// Get the time between the start of the program and this `Instant`.
 	#[inline]
 	pub fn as_duration(self) -> Duration {
 		Duration::from_micros(self.0)
@@ -107,18 +112,35 @@ impl Instant {
 		Some(Self(self.0.checked_sub(rhs.as_micros().try_into().ok()?)?))
 	}
 
-	/// Perform a checked subtraction of the `rhs` Instant onto this `Instant`.
-	/// This function will return `None` if the subtraction would have resulted
-	/// in an underflow.
-	pub fn checked_sub_instant(&self, rhs: Self) -> Option<Self> {
-		Some(Self(self.0.checked_sub(rhs.0)?))
+	/// Get the amount of time between an earlier `rhs` and this `Instant`.
+	/// This function will return `None` if `rhs` is later than `self`.
+	pub fn checked_sub_instant(&self, rhs: Self) -> Option<Duration> {
+		Some(Duration::from_micros(self.0.checked_sub(rhs.0)?))
 	}
 
 	/// Return how long has elapsed since the time recorded in this `Instant`. A
 	/// panic will occur if `self` measures a time in the future that has not
 	/// yet occurred.
 	pub fn elapsed(&self) -> Duration {
-		(Instant::now() - *self).as_duration()
+		Instant::now() - *self
+	}
+
+	/// Check if at least `dur` has passed since the time recorded in this
+	/// `Instant`. Unlike [`Instant::elapsed()`] this will not panic if `self`
+	/// is in the future, it will just return `false`.
+	///
+	/// # Examples
+	/// ```
+	/// if last_log.is_elapsed(Duration::from_millis(250)) {
+	/// 	println!("left: {}", left.get_position()?);
+	/// 	last_log = Instant::now();
+	/// }
+	/// ```
+	pub fn is_elapsed(&self, dur: Duration) -> bool {
+		Instant::now()
+			.checked_sub_instant(*self)
+			.map(|t| t >= dur)
+			.unwrap_or(false)
 	}
 
 	/// Return the duration between this `Instant` and an earlier `Instant`.
@@ -127,11 +149,14 @@ impl Instant {
 	/// Panics if `earlier` happened later than `self`. Use
 	/// [`Instant::checked_sub_instant()`] instead to catch underflow.
 	pub fn duration_since(&self, earlier: Instant) -> Duration {
-		Duration::from_micros(
-			self.checked_sub_instant(earlier)
-				.expect("supplied instant is later then self")
-				.0,
-		)
+		self.checked_sub_instant(earlier)
+			.expect("supplied instant is later then self")
+	}
+
+	/// Return the duration between this `Instant` and an earlier `Instant`, or
+	/// zero if `earlier` is actually later than `self`.
+	pub fn saturating_duration_since(&self, earlier: Instant) -> Duration {
+		self.checked_sub_instant(earlier).unwrap_or(Duration::ZERO)
 	}
 }
 
@@ -159,8 +184,9 @@ impl Sub<Duration> for Instant {
 	}
 }
 
+/// The amount of time between two instants, where `rhs` is the earlier one.
 impl Sub<Instant> for Instant {
-	type Output = Instant;
+	type Output = Duration;
 
 	fn sub(self, rhs: Instant) -> Self::Output {
 		self.checked_sub_instant(rhs)
@@ -168,32 +194,77 @@ impl Sub<Instant> for Instant {
 	}
 }
 
+/// A timer which ticks every `period`, for running loops at a fixed rate.
+///
+/// The time of the next tick is tracked as an absolute [`Instant`] and waited
+/// on with [`Task::delay_until()`], so time spent in the loop body or being
+/// preempted does not cause the period to drift.
 pub struct Interval {
 	period: Duration,
-	last: Instant,
+	next: Instant,
 }
 
 impl Interval {
 	pub fn new(period: Duration) -> Self {
 		Interval {
 			period,
-			last: Instant::now(),
+			next: Instant::now() + period,
 		}
 	}
 
+	/// Restart the current period from now, as if this interval had just been
+	/// created. The next call to [`Interval::delay()`] will wait for a full
+	/// period.
+	pub fn reset(&mut self) {
+		self.next = Instant::now() + self.period;
+	}
+
 	pub fn time_left(&self) -> Duration {
-		(self.last + self.period)
-			.checked_sub_instant(Instant::now())
-			.map(|t| Duration::from_micros(t.as_micros()))
-			.unwrap_or(Duration::from_micros(0))
+		self.next.saturating_duration_since(Instant::now())
 	}
 
 	pub fn delay(&mut self) {
-		// Check if we do actually need to delay for our next period
-		if let Some(t) = (self.last + self.period).checked_sub_instant(Instant::now()) {
-			Task::delay(Duration::from_micros(t.as_micros()));
+		Task::delay_until(self.next);
+		self.next += self.period;
+	}
+
+	/// Delay until the next tick of this interval which is still in the
+	/// future, dropping any ticks which have already been missed. Returns how
+	/// many ticks were skipped, which is `0` if the task kept up.
+	///
+	/// This differs from [`Interval::delay()`], which always advances by
+	/// exactly one period. After a long stall `delay` will return immediately
+	/// once for every missed tick until it has caught up, whereas this returns
+	/// to a steady rate straight away. Prefer this for control loops, where a
+	/// burst of iterations with almost no time between them is not useful.
+	///
+	/// # Examples
+	/// ```
+	/// let mut timer = Interval::new(Duration::from_millis(10));
+	/// loop {
+	/// 	// control loop
+	/// 	let missed = timer.delay_skip_missed();
+	/// 	if missed > 0 {
+	/// 		println!("control loop fell behind by {} ticks", missed);
+	/// 	}
+	/// }
+	/// ```
+	pub fn delay_skip_missed(&mut self) -> u32 {
+		let period = self.period.as_micros() as u64;
+		if period == 0 {
+			return 0;
 		}
-		self.last += self.period;
+
+		// How many ticks have come due since the one we are waiting for
+		let missed = Instant::now()
+			.checked_sub_instant(self.next)
+			.map(|t| t.as_micros() as u64 / period + 1)
+			.unwrap_or(0);
+		let next = Instant::from_micros(self.next.as_micros() + missed * period);
+
+		Task::delay_until(next);
+		self.next = next + self.period;
+		missed as u32
 	}
 
 	pub fn action(&'_ mut self) -> impl Action + '_ {
@@ -206,10 +277,7 @@ impl Interval {
 			// return that we are now ready. Otherwise we aren't ready and we should sleep
 			// until we will be ready next.
 			fn poll(&mut self) -> Poll<Self::Output> {
-				if (self.0.last + self.0.period)
-					.checked_sub_instant(Instant::now())
-					.is_some()
-				{
+				if Instant::now() < self.0.next {
 					Poll::Waiting
 				} else {
 					Poll::Complete(())
@@ -219,13 +287,8 @@ impl Interval {
 			// If this gets called it is assumed that we are not yet complete so we must
 			// have some time that we need to wait for.
 			fn next(&mut self) -> NextSleep {
-				let sleep = NextSleep::Timestamp(
-					(self.0.last + self.0.period)
-						.checked_sub_instant(Instant::now())
-						.map(|i| i.as_duration())
-						.unwrap_or(Duration::from_micros(0)),
-				);
-				self.0.last += self.0.period;
+				let sleep = NextSleep::Deadline(self.0.next);
+				self.0.next += self.0.period;
 				sleep
 			}
 		}
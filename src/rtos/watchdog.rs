@@ -0,0 +1,118 @@
+//! Stopping the robot when a control loop stops running.
+
+use crate::devices::motor::stop_all_motors;
+use crate::rtos::{
+	tasks::{Task, TaskBuilder},
+	time::Instant,
+};
+use crate::util::duration_to_ms;
+
+use alloc::{string::String, sync::Arc};
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use core::time::Duration;
+
+/// How many times per timeout the watchdog checks if it has been fed.
+const CHECKS_PER_TIMEOUT: u32 = 4;
+
+struct WatchdogInner {
+	/// When the watchdog was last fed in milliseconds, wrapping.
+	last_fed: AtomicU32,
+	stopped: AtomicBool,
+}
+
+/// Calls a function if it is not fed often enough, such as when the task
+/// feeding it has stalled or deadlocked.
+///
+/// A task is spawned to monitor the watchdog at the highest priority, so it
+/// still runs if the stalled task is stuck in a busy loop. Once the watchdog
+/// has fired it will not fire again until it has been fed. The monitor task
+/// stops when the watchdog is dropped.
+///
+/// # Examples
+/// ```
+/// let watchdog = Watchdog::new(Duration::from_millis(100));
+/// let mut timer = Interval::new(Duration::from_millis(10));
+/// loop {
+/// 	drive.arcade(throttle, turn)?;
+/// 	watchdog.feed();
+/// 	timer.delay();
+/// }
+/// ```
+pub struct Watchdog {
+	inner: Arc<WatchdogInner>,
+	task: Task,
+}
+
+impl Watchdog {
+	/// Create a new watchdog which stops every motor plugged into the V5 Brain
+	/// if it is not fed within `timeout`.
+	///
+	/// # Panics
+	/// Panics if the monitor task cannot be spawned, refer to
+	/// [`TaskBuilder::spawn()`].
+	pub fn new(timeout: Duration) -> Watchdog {
+		Self::with_callback(timeout, stop_all_motors)
+	}
+
+	/// Create a new watchdog which calls `f` if it is not fed within
+	/// `timeout`. `f` is called from the monitor task.
+	///
+	/// # Panics
+	/// Panics if the monitor task cannot be spawned, refer to
+	/// [`TaskBuilder::spawn()`].
+	pub fn with_callback<F: Fn() + Send + 'static>(timeout: Duration, f: F) -> Watchdog {
+		let inner = Arc::new(WatchdogInner {
+			last_fed: AtomicU32::new(now_ms()),
+			stopped: AtomicBool::new(false),
+		});
+
+		let task = {
+			let inner = inner.clone();
+			let timeout_ms = duration_to_ms(timeout);
+			TaskBuilder::new()
+				.name(String::from("watchdog"))
+				.priority(Task::PRIORITY_MAX)
+				.spawn(move || {
+					let mut fired = false;
+					while !inner.stopped.load(Ordering::Relaxed) {
+						let last = inner.last_fed.load(Ordering::Relaxed);
+						if now_ms().wrapping_sub(last) > timeout_ms {
+							if !fired {
+								f();
+								fired = true;
+							}
+						} else {
+							fired = false;
+						}
+						Task::delay(timeout / CHECKS_PER_TIMEOUT);
+					}
+				})
+				.expect("failed to spawn watchdog")
+		};
+
+		Watchdog { inner, task }
+	}
+
+	/// Reset the watchdog's timeout, this must be called more often than the
+	/// timeout to stop the watchdog from firing.
+	#[inline]
+	pub fn feed(&self) {
+		self.inner.last_fed.store(now_ms(), Ordering::Relaxed);
+	}
+
+	/// Get the task which is monitoring this watchdog.
+	#[inline]
+	pub fn task(&self) -> &Task {
+		&self.task
+	}
+}
+
+impl Drop for Watchdog {
+	fn drop(&mut self) {
+		self.inner.stopped.store(true, Ordering::Relaxed);
+	}
+}
+
+fn now_ms() -> u32 {
+	Instant::now().as_millis() as u32
+}
@@ -0,0 +1,176 @@
+//! A supervisor which watches the battery and motors for unsafe operating
+//! conditions from a background task.
+//!
+//! Rather than scattering temperature and capacity checks throughout the
+//! control code, a [`SafetySupervisor`] can be configured once during
+//! [`Robot::new()`][crate::Robot::new()] and will notify registered callbacks
+//! whenever a threshold is crossed.
+
+use crate::bindings;
+use crate::devices::{controller::Battery, motor::Motor};
+use crate::rtos::{
+	tasks::{Task, TaskBuilder},
+	time::Interval,
+};
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::time::Duration;
+
+/// A condition which has been detected by the [`SafetySupervisor`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SafetyEvent {
+	/// The battery temperature has risen above the configured threshold,
+	/// contains the temperature in degrees Celsius.
+	BatteryHot(f64),
+	/// The battery capacity has fallen below the configured threshold,
+	/// contains the remaining capacity as a percentage.
+	BatteryLow(f64),
+	/// A monitored motor has reported that it is over temperature, contains
+	/// the port of the motor.
+	MotorHot(u8),
+}
+
+/// A builder for a background task which monitors the battery and motors,
+/// invoking callbacks when a threshold is crossed.
+///
+/// Callbacks are only invoked on the transition into an unsafe state, they will
+/// not be called again for the same condition until it has cleared and been
+/// crossed again.
+///
+/// # Examples
+/// ```
+/// SafetySupervisor::new()
+/// 	.battery_temperature(50.0)
+/// 	.monitor_motor(&lift)
+/// 	.on_event(|event| println!("safety: {:?}", event))
+/// 	.spawn();
+/// ```
+pub struct SafetySupervisor {
+	period: Duration,
+	battery_temperature: f64,
+	battery_capacity: f64,
+	motors: Vec<u8>,
+	callbacks: Vec<Box<dyn FnMut(SafetyEvent) + Send>>,
+}
+
+impl SafetySupervisor {
+	/// The default period between each check of the battery and motors.
+	pub const DEFAULT_PERIOD: Duration = Duration::from_millis(100);
+	/// The default battery temperature threshold in degrees Celsius.
+	pub const DEFAULT_BATTERY_TEMPERATURE: f64 = 50.0;
+	/// The default battery capacity threshold as a percentage.
+	pub const DEFAULT_BATTERY_CAPACITY: f64 = 20.0;
+
+	/// Create a new supervisor with the default thresholds and no motors or
+	/// callbacks registered.
+	pub fn new() -> SafetySupervisor {
+		SafetySupervisor {
+			period: Self::DEFAULT_PERIOD,
+			battery_temperature: Self::DEFAULT_BATTERY_TEMPERATURE,
+			battery_capacity: Self::DEFAULT_BATTERY_CAPACITY,
+			motors: Vec::new(),
+			callbacks: Vec::new(),
+		}
+	}
+
+	/// Set how often the supervisor should check the battery and motors.
+	pub fn period(mut self, period: Duration) -> SafetySupervisor {
+		self.period = period;
+		self
+	}
+
+	/// Set the battery temperature in degrees Celsius above which a
+	/// [`SafetyEvent::BatteryHot`] will be raised.
+	pub fn battery_temperature(mut self, celsius: f64) -> SafetySupervisor {
+		self.battery_temperature = celsius;
+		self
+	}
+
+	/// Set the battery capacity as a percentage below which a
+	/// [`SafetyEvent::BatteryLow`] will be raised.
+	pub fn battery_capacity(mut self, percent: f64) -> SafetySupervisor {
+		self.battery_capacity = percent;
+		self
+	}
+
+	/// Add a motor to be checked with [`Motor::is_over_temp()`]. Only the port
+	/// of the motor is recorded, ownership of the motor remains with the
+	/// caller.
+	pub fn monitor_motor(mut self, motor: &Motor) -> SafetySupervisor {
+		self.motors.push(motor.get_port());
+		self
+	}
+
+	/// Register a callback which is invoked from the supervisor task whenever a
+	/// threshold is crossed.
+	pub fn on_event<F: FnMut(SafetyEvent) + Send + 'static>(mut self, f: F) -> SafetySupervisor {
+		self.callbacks.push(Box::new(f));
+		self
+	}
+
+	/// Spawn the background task which will monitor for unsafe conditions.
+	///
+	/// # Panics
+	/// Panics if the task cannot be spawned, refer to [`TaskBuilder::spawn()`].
+	pub fn spawn(self) -> Task {
+		TaskBuilder::new()
+			.name(String::from("safety"))
+			.spawn(move || self.run())
+			.expect("failed to spawn safety supervisor")
+	}
+
+	fn run(mut self) {
+		let mut timer = Interval::new(self.period);
+		let mut battery_hot = false;
+		let mut battery_low = false;
+		let mut motors_hot: Vec<bool> = self.motors.iter().map(|_| false).collect();
+
+		loop {
+			// Errors while reading are treated as no change in state, a missed
+			// sample will be picked up again on the next period
+			if let Ok(temp) = Battery::get_temperature() {
+				let hot = temp > self.battery_temperature;
+				if hot && !battery_hot {
+					self.raise(SafetyEvent::BatteryHot(temp));
+				}
+				battery_hot = hot;
+			}
+
+			if let Ok(capacity) = Battery::get_capacity() {
+				let low = capacity < self.battery_capacity;
+				if low && !battery_low {
+					self.raise(SafetyEvent::BatteryLow(capacity));
+				}
+				battery_low = low;
+			}
+
+			for i in 0..self.motors.len() {
+				let port = self.motors[i];
+				// We don't own the motor so we have to go to PROS directly
+				let hot = match unsafe { bindings::motor_is_over_temp(port) } {
+					1 => true,
+					0 => false,
+					_ => continue,
+				};
+				if hot && !motors_hot[i] {
+					self.raise(SafetyEvent::MotorHot(port));
+				}
+				motors_hot[i] = hot;
+			}
+
+			timer.delay();
+		}
+	}
+
+	fn raise(&mut self, event: SafetyEvent) {
+		for cb in self.callbacks.iter_mut() {
+			cb(event);
+		}
+	}
+}
+
+impl Default for SafetySupervisor {
+	fn default() -> Self {
+		Self::new()
+	}
+}
@@ -0,0 +1,100 @@
+//! Driving a differential drivetrain from joystick inputs.
+
+use crate::devices::motor::MotorGroup;
+use crate::devices::DeviceError;
+use crate::math::FloatMath;
+
+/// The voltage in millivolts which a full input of 1 is scaled to.
+const MAX_VOLTAGE: f64 = 12000.0;
+
+/// A differential drivetrain, with a group of motors driving each side of the
+/// robot.
+///
+/// All of the inputs are within `[-1, 1]` and are scaled to the full voltage
+/// range of the motors, anything outside of this range is clamped. The motors
+/// on one side will usually need to be reversed so that a positive input drives
+/// both sides forwards.
+///
+/// # Examples
+/// ```
+/// let mut drive = Drivetrain::new(left, right);
+/// loop {
+/// 	drive.arcade(
+/// 		controller.get_analog(Axis::LeftY)?,
+/// 		controller.get_analog(Axis::RightX)?,
+/// 	)?;
+/// 	Task::delay(Duration::from_millis(10));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Drivetrain {
+	pub left: MotorGroup,
+	pub right: MotorGroup,
+}
+
+impl Drivetrain {
+	/// Create a new drivetrain from the motors on each side of the robot.
+	pub fn new(left: MotorGroup, right: MotorGroup) -> Self {
+		Drivetrain { left, right }
+	}
+
+	/// Drive each side of the robot directly, such as from the Y axis of each
+	/// joystick.
+	pub fn tank(&mut self, left: f64, right: f64) -> Result<(), DeviceError> {
+		let left = self.left.move_voltage(to_voltage(left));
+		let right = self.right.move_voltage(to_voltage(right));
+		left.and(right)
+	}
+
+	/// Drive the robot with one input for moving forwards and backwards, and
+	/// another for turning, where a positive `turn` turns clockwise. If the two
+	/// combined would be more than full power on either side both sides are
+	/// scaled down together, so the robot still turns at full throttle.
+	pub fn arcade(&mut self, throttle: f64, turn: f64) -> Result<(), DeviceError> {
+		let throttle = throttle.clamp(-1.0, 1.0);
+		let turn = turn.clamp(-1.0, 1.0);
+		let (left, right) = normalise(throttle + turn, throttle - turn);
+		self.tank(left, right)
+	}
+
+	/// Drive the robot so that `curvature` controls how tightly it turns
+	/// rather than how fast, so the robot follows the same arc no matter the
+	/// throttle. This makes high speed driving much easier to control. As the
+	/// robot cannot turn on the spot like this, `quick_turn` switches to
+	/// [`Drivetrain::arcade()`] for turning while stationary.
+	pub fn curvature(
+		&mut self,
+		throttle: f64,
+		curvature: f64,
+		quick_turn: bool,
+	) -> Result<(), DeviceError> {
+		if quick_turn {
+			return self.arcade(throttle, curvature);
+		}
+		let throttle = throttle.clamp(-1.0, 1.0);
+		let turn = throttle.abs() * curvature.clamp(-1.0, 1.0);
+		let (left, right) = normalise(throttle + turn, throttle - turn);
+		self.tank(left, right)
+	}
+
+	/// Stop both sides of the drivetrain.
+	pub fn stop(&mut self) -> Result<(), DeviceError> {
+		let left = self.left.stop();
+		let right = self.right.stop();
+		left.and(right)
+	}
+}
+
+/// Scale both sides down by the same amount so neither is more than 1.
+fn normalise(left: f64, right: f64) -> (f64, f64) {
+	let max = left.abs().max(right.abs());
+	if max > 1.0 {
+		(left / max, right / max)
+	} else {
+		(left, right)
+	}
+}
+
+fn to_voltage(input: f64) -> i16 {
+	(input.clamp(-1.0, 1.0) * MAX_VOLTAGE) as i16
+}
@@ -0,0 +1,91 @@
+//! A framework for organising the mechanisms of a robot into subsystems which
+//! are updated at a fixed rate from a single task.
+//!
+//! The V5 Brain only has a single core available to user code, giving every
+//! mechanism its own task multiplies the context switching overhead. Instead
+//! each mechanism can implement [`Subsystem`] and be registered with a
+//! [`Scheduler`] which will update all of them from one loop.
+
+pub mod drive;
+
+use crate::rtos::{
+	tasks::{Task, TaskBuilder},
+	time::{Instant, Interval},
+};
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::time::Duration;
+
+/// A mechanism of the robot which is periodically updated by a [`Scheduler`].
+pub trait Subsystem: Send {
+	/// Update the state of this subsystem. `dt` is the amount of time that has
+	/// elapsed since the previous update, on the first update this will be the
+	/// period of the scheduler.
+	fn update(&mut self, dt: Duration);
+}
+
+/// Runs a collection of [`Subsystem`]s at a fixed rate from a single task.
+///
+/// # Ordering
+/// Every period each subsystem is updated once, in the same order they were
+/// added to the scheduler. Subsystems are updated one after the other from the
+/// same task, so an update will never run concurrently with another update
+/// from the same scheduler. All subsystems receive the same `dt` within a
+/// single period.
+///
+/// # Examples
+/// ```
+/// let mut scheduler = Scheduler::new();
+/// scheduler.add(Box::new(lift));
+/// scheduler.add(Box::new(intake));
+/// scheduler.spawn(Duration::from_millis(10));
+/// ```
+#[derive(Default)]
+pub struct Scheduler {
+	subsystems: Vec<Box<dyn Subsystem>>,
+}
+
+impl Scheduler {
+	/// Create a new scheduler with no subsystems.
+	pub fn new() -> Scheduler {
+		Scheduler {
+			subsystems: Vec::new(),
+		}
+	}
+
+	/// Register a subsystem with this scheduler, it will be updated after all
+	/// the subsystems that were added before it.
+	pub fn add(&mut self, subsystem: Box<dyn Subsystem>) {
+		self.subsystems.push(subsystem);
+	}
+
+	/// Update every subsystem once per `period` from the current task. This
+	/// function never returns.
+	pub fn run(&mut self, period: Duration) -> ! {
+		let mut timer = Interval::new(period);
+		let mut last: Option<Instant> = None;
+
+		loop {
+			let now = Instant::now();
+			let dt = last.map(|l| now.duration_since(l)).unwrap_or(period);
+			last = Some(now);
+
+			for subsystem in self.subsystems.iter_mut() {
+				subsystem.update(dt);
+			}
+
+			timer.delay();
+		}
+	}
+
+	/// Spawn a new task which will [`Scheduler::run()`] this scheduler.
+	///
+	/// # Panics
+	/// Panics if the task cannot be spawned, refer to [`TaskBuilder::spawn()`].
+	pub fn spawn(mut self, period: Duration) -> Task {
+		TaskBuilder::new()
+			.name(String::from("scheduler"))
+			.spawn(move || self.run(period))
+			.expect("failed to spawn scheduler")
+	}
+}
@@ -1,15 +1,39 @@
+//! Miscellaneous utilities which are useful alongside the rest of the crate.
+
+use crate::math::FloatMath;
+
 use alloc::{string::String, vec::Vec};
+use core::{ffi::CStr, fmt, ops::Deref, time::Duration};
 
+#[doc(hidden)]
 pub const PROS_ERR: i32 = i32::MAX;
+#[doc(hidden)]
 pub const PROS_ERR_U32: u32 = i32::MAX as u32;
+#[doc(hidden)]
 pub const PROS_ERR_F: f64 = f64::INFINITY;
+#[doc(hidden)]
 pub const PROS_ERR_VISION_OBJECT_SIG: u8 = 255;
 
+#[doc(hidden)]
 pub fn get_errno() -> libc::c_int {
 	unsafe { *crate::bindings::__errno() }
 }
 
-#[allow(unused)]
+#[doc(hidden)]
+pub fn clear_errno() {
+	unsafe { *crate::bindings::__errno() = 0 }
+}
+
+/// Convert a duration into the whole milliseconds which PROS takes for timeouts
+/// and delays. Anything under a millisecond is truncated, and durations which
+/// are too long to fit saturate at `u32::MAX`, which PROS treats as waiting
+/// forever.
+#[inline]
+pub fn duration_to_ms(d: Duration) -> u32 {
+	d.as_millis().min(u32::MAX as u128) as u32
+}
+
+#[doc(hidden)]
 pub fn cstring_from(cstr: *const libc::c_char) -> String {
 	unsafe {
 		String::from_utf8_lossy(core::slice::from_raw_parts(
@@ -20,9 +44,368 @@ pub fn cstring_from(cstr: *const libc::c_char) -> String {
 	}
 }
 
+#[doc(hidden)]
 pub fn to_cstring(s: String) -> Vec<u8> {
 	let mut bytes = s.into_bytes();
 	bytes.reserve(bytes.len() + 1);
 	bytes.push(0);
 	bytes
 }
+
+/// A fixed capacity string which is stored entirely on the stack.
+///
+/// This is useful for formatting text which is updated every loop, such as
+/// the controller or brain screen, without allocating on the small heap of the
+/// V5 Brain. The contents are always followed by a NUL byte so they can be
+/// passed straight to PROS, which means at most `N - 1` bytes can be stored.
+///
+/// Writing more text than will fit truncates it on a character boundary and
+/// returns [`fmt::Error`], the text that did fit is kept.
+///
+/// # Examples
+/// ```
+/// use core::fmt::Write;
+///
+/// let mut text = StackString::<32>::new();
+/// let _ = write!(text, "battery: {}%", Battery::get_capacity()?);
+/// controller.set_text(0, 0, &text);
+/// ```
+#[derive(Clone)]
+pub struct StackString<const N: usize> {
+	buf: [u8; N],
+	len: usize,
+}
+
+impl<const N: usize> StackString<N> {
+	/// Create a new empty string.
+	///
+	/// # Panics
+	/// Panics if `N` is zero, as there would be no room for the NUL byte.
+	pub const fn new() -> Self {
+		assert!(N > 0, "a StackString needs room for at least the NUL byte");
+		StackString {
+			buf: [0; N],
+			len: 0,
+		}
+	}
+
+	/// The maximum amount of bytes this string can hold.
+	#[inline]
+	pub const fn capacity(&self) -> usize {
+		N - 1
+	}
+
+	/// The amount of bytes currently stored in this string.
+	#[inline]
+	pub const fn len(&self) -> usize {
+		self.len
+	}
+
+	/// Check if this string is empty.
+	#[inline]
+	pub const fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Remove all of the text from this string.
+	#[inline]
+	pub fn clear(&mut self) {
+		self.len = 0;
+		self.buf[0] = 0;
+	}
+
+	/// Get the contents of this string as a `&str`.
+	#[inline]
+	pub fn as_str(&self) -> &str {
+		// Only whole `&str`s split on character boundaries are ever written
+		unsafe { core::str::from_utf8_unchecked(&self.buf[..self.len]) }
+	}
+
+	/// Get the contents of this string as a NUL terminated C string. If the
+	/// text contains a NUL byte the C string will end there.
+	#[inline]
+	pub fn as_c_str(&self) -> &CStr {
+		CStr::from_bytes_until_nul(&self.buf[..=self.len]).unwrap()
+	}
+}
+
+impl<const N: usize> fmt::Write for StackString<N> {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		let available = self.capacity() - self.len;
+		let mut take = s.len().min(available);
+		while !s.is_char_boundary(take) {
+			take -= 1;
+		}
+
+		self.buf[self.len..self.len + take].copy_from_slice(&s.as_bytes()[..take]);
+		self.len += take;
+		self.buf[self.len] = 0;
+
+		if take == s.len() {
+			Ok(())
+		} else {
+			Err(fmt::Error)
+		}
+	}
+}
+
+impl<const N: usize> Default for StackString<N> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<const N: usize> Deref for StackString<N> {
+	type Target = str;
+
+	fn deref(&self) -> &str {
+		self.as_str()
+	}
+}
+
+impl<const N: usize> fmt::Display for StackString<N> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+impl<const N: usize> fmt::Debug for StackString<N> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Debug::fmt(self.as_str(), f)
+	}
+}
+
+/// A fixed capacity queue which is stored entirely on the stack. Once it is
+/// full, pushing a new value pushes out the oldest one.
+///
+/// This is useful for keeping a short history of readings from a sensor, for
+/// example to smooth out noise with [`RingBuffer::mean()`] or
+/// [`RingBuffer::median()`].
+///
+/// # Examples
+/// ```
+/// let mut history = RingBuffer::<i32, 5>::new();
+/// loop {
+/// 	history.push(distance.get_distance()?);
+/// 	if history.is_full() && history.median().unwrap() < 100.0 {
+/// 		break;
+/// 	}
+/// 	Task::delay(Duration::from_millis(20));
+/// }
+/// ```
+#[derive(Clone)]
+pub struct RingBuffer<T, const N: usize> {
+	buf: [Option<T>; N],
+	// Where the next value will be written to
+	head: usize,
+	len: usize,
+}
+
+impl<T, const N: usize> RingBuffer<T, N> {
+	/// Create a new empty ring buffer.
+	///
+	/// # Panics
+	/// Panics if `N` is zero, as there would be no room for any values.
+	pub const fn new() -> Self {
+		assert!(N > 0, "a RingBuffer needs room for at least one value");
+		RingBuffer {
+			buf: [const { None }; N],
+			head: 0,
+			len: 0,
+		}
+	}
+
+	/// The maximum amount of values this buffer can hold.
+	#[inline]
+	pub const fn capacity(&self) -> usize {
+		N
+	}
+
+	/// The amount of values currently stored in this buffer.
+	#[inline]
+	pub const fn len(&self) -> usize {
+		self.len
+	}
+
+	/// Check if this buffer is empty.
+	#[inline]
+	pub const fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Check if this buffer is full, meaning the next push will remove the
+	/// oldest value.
+	#[inline]
+	pub const fn is_full(&self) -> bool {
+		self.len == N
+	}
+
+	/// Remove all of the values from this buffer.
+	pub fn clear(&mut self) {
+		self.buf.iter_mut().for_each(|v| *v = None);
+		self.head = 0;
+		self.len = 0;
+	}
+
+	/// Add a value to the buffer. If the buffer was full then the oldest value
+	/// is removed and returned.
+	pub fn push(&mut self, value: T) -> Option<T> {
+		let old = self.buf[self.head].replace(value);
+		self.head = (self.head + 1) % N;
+		self.len = (self.len + 1).min(N);
+		old
+	}
+
+	/// Get the value which was most recently pushed.
+	pub fn latest(&self) -> Option<&T> {
+		self.buf[(self.head + N - 1) % N].as_ref()
+	}
+
+	/// Get the oldest value which is still in the buffer.
+	pub fn oldest(&self) -> Option<&T> {
+		self.buf[(self.head + N - self.len) % N].as_ref()
+	}
+
+	/// Iterate over the values in the buffer, from the oldest to the latest.
+	pub fn iter(&self) -> impl Iterator<Item = &T> + '_ {
+		let start = self.head + N - self.len;
+		(start..start + self.len).filter_map(move |i| self.buf[i % N].as_ref())
+	}
+}
+
+impl<T: Copy + Into<f64>, const N: usize> RingBuffer<T, N> {
+	/// Get the mean of the values in the buffer, or `None` if it is empty.
+	pub fn mean(&self) -> Option<f64> {
+		if self.is_empty() {
+			return None;
+		}
+		let sum: f64 = self.iter().map(|&v| v.into()).sum();
+		Some(sum / self.len as f64)
+	}
+
+	/// Get the median of the values in the buffer, or `None` if it is empty.
+	/// With an even amount of values this is the mean of the middle two.
+	///
+	/// Unlike [`RingBuffer::mean()`] a single wild reading has no effect on
+	/// the median, which makes it better for rejecting sensor glitches.
+	pub fn median(&self) -> Option<f64> {
+		if self.is_empty() {
+			return None;
+		}
+		let mut sorted = [0.0; N];
+		for (s, &v) in sorted.iter_mut().zip(self.iter()) {
+			*s = v.into();
+		}
+		let sorted = &mut sorted[..self.len];
+		sorted.sort_unstable_by(f64::total_cmp);
+
+		let mid = self.len / 2;
+		if self.len.is_multiple_of(2) {
+			Some((sorted[mid - 1] + sorted[mid]) / 2.0)
+		} else {
+			Some(sorted[mid])
+		}
+	}
+}
+
+impl<T, const N: usize> Default for RingBuffer<T, N> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for RingBuffer<T, N> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_list().entries(self.iter()).finish()
+	}
+}
+
+/// A moving average over the last `N` readings of a sensor, which smooths out
+/// the noise in its readings.
+///
+/// `read` can be any closure which takes a reading, so this works with any
+/// sensor. Optionally readings which are too far from the rest can be thrown
+/// away, see [`Filtered::with_outlier_rejection()`].
+///
+/// # Examples
+/// ```
+/// let mut distance = Filtered::<_, 5>::new(|| sensor.get_distance().map(|d| d as f64))
+/// 	.with_outlier_rejection(50.0);
+/// loop {
+/// 	let mm = distance.sample()?;
+/// 	// control loop
+/// 	Task::delay(Duration::from_millis(20));
+/// }
+/// ```
+pub struct Filtered<F, const N: usize> {
+	read: F,
+	window: RingBuffer<f64, N>,
+	max_deviation: Option<f64>,
+	// How many readings in a row have been thrown away as outliers
+	rejected: usize,
+}
+
+impl<F, E, const N: usize> Filtered<F, N>
+where
+	F: FnMut() -> Result<f64, E>,
+{
+	/// Create a new filter which takes readings with `read`.
+	pub const fn new(read: F) -> Self {
+		Filtered {
+			read,
+			window: RingBuffer::new(),
+			max_deviation: None,
+			rejected: 0,
+		}
+	}
+
+	/// Throw away readings which are further than `max_deviation` from the
+	/// median of the readings before them. This only starts once `N` readings
+	/// have been taken.
+	///
+	/// If `N` readings in a row are thrown away the sensor is assumed to have
+	/// genuinely changed rather than glitched, and the filter starts again
+	/// from the latest reading.
+	pub fn with_outlier_rejection(mut self, max_deviation: f64) -> Self {
+		self.max_deviation = Some(max_deviation);
+		self
+	}
+
+	/// Take a new reading and return the average of the readings in the
+	/// window. Readings which fail are passed through as errors and are not
+	/// added to the window.
+	pub fn sample(&mut self) -> Result<f64, E> {
+		let reading = (self.read)()?;
+
+		let outlier = match (self.max_deviation, self.window.median()) {
+			(Some(max), Some(median)) if self.window.is_full() => (reading - median).abs() > max,
+			_ => false,
+		};
+
+		if !outlier {
+			self.rejected = 0;
+			self.window.push(reading);
+		} else {
+			self.rejected += 1;
+			if self.rejected >= N {
+				self.reset();
+				self.window.push(reading);
+			}
+		}
+
+		// There is always at least one reading in the window by now
+		Ok(self.window.mean().unwrap_or(reading))
+	}
+
+	/// Get the current average without taking a new reading, or `None` if no
+	/// readings have been taken yet.
+	pub fn value(&self) -> Option<f64> {
+		self.window.mean()
+	}
+
+	/// Forget all of the previous readings.
+	pub fn reset(&mut self) {
+		self.window.clear();
+		self.rejected = 0;
+	}
+}